@@ -0,0 +1,62 @@
+use crate::commands::git_state::run_blocking;
+use crate::core::git_credentials;
+use crate::models::git_repository::{CredentialInfo, HostCredential};
+use tracing::{error, info, instrument};
+
+/// Save the credential used for a remote host in the OS keyring
+#[tauri::command]
+#[instrument(skip(credential))]
+pub async fn save_credential(host: String, credential: HostCredential) -> Result<(), String> {
+    info!("Saving credential");
+
+    // The keyring may block on an unlock prompt
+    run_blocking(move || {
+        git_credentials::save_host_credential(&host, &credential).map_err(|e| {
+            error!("Failed to save credential: {:?}", e);
+            format!("Could not save credential for '{}': {}", host, e)
+        })
+    })
+    .await?;
+
+    info!("Credential saved successfully");
+    Ok(())
+}
+
+/// Get the saved credential for a remote host, without its secret
+#[tauri::command]
+#[instrument]
+pub async fn get_credential_info(host: String) -> Result<Option<CredentialInfo>, String> {
+    info!("Getting credential info");
+
+    let credential_info = run_blocking(move || {
+        git_credentials::get_host_credential_info(&host).map_err(|e| {
+            error!("Failed to read credential: {:?}", e);
+            format!("Could not read credential for '{}': {}", host, e)
+        })
+    })
+    .await?;
+
+    info!(
+        found = credential_info.is_some(),
+        "Credential info retrieved"
+    );
+    Ok(credential_info)
+}
+
+/// Remove the saved credential for a remote host
+#[tauri::command]
+#[instrument]
+pub async fn delete_credential(host: String) -> Result<(), String> {
+    info!("Deleting credential");
+
+    run_blocking(move || {
+        git_credentials::delete_host_credential(&host).map_err(|e| {
+            error!("Failed to delete credential: {:?}", e);
+            format!("Could not delete credential for '{}': {}", host, e)
+        })
+    })
+    .await?;
+
+    info!("Credential deleted successfully");
+    Ok(())
+}
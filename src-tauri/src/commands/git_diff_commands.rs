@@ -1,6 +1,5 @@
 use crate::commands::git_state::GitState;
-use crate::core::git_engine::GitEngine;
-use crate::models::git_repository::FileDiff;
+use crate::models::git_repository::{DiffRequestOptions, FileDiff};
 use tauri::State;
 use tracing::{error, info, instrument};
 
@@ -9,25 +8,23 @@ use tracing::{error, info, instrument};
 #[instrument(skip(state))]
 pub async fn get_file_diff_unstaged(
     file_path: String,
+    options: Option<DiffRequestOptions>,
+    repo_id: Option<String>,
     state: State<'_, GitState>,
 ) -> Result<FileDiff, String> {
     info!(file_path, "Getting unstaged diff");
 
-    let current = state.current_repo.lock().unwrap();
-    let path = current.as_ref().ok_or_else(|| {
-        error!("No repository is currently open");
-        "No repository is currently open".to_string()
-    })?;
-
-    let engine = GitEngine::open(path).map_err(|e| {
-        error!("Failed to open repository: {:?}", e);
-        format!("Could not access repository: {}", e)
-    })?;
-
-    let diff = engine.get_file_diff_unstaged(&file_path).map_err(|e| {
-        error!("Failed to get unstaged diff: {:?}", e);
-        format!("Could not retrieve unstaged diff: {}", e)
-    })?;
+    let options = options.unwrap_or_default();
+    let diff = state
+        .run(repo_id.as_deref(), move |engine| {
+            engine
+                .get_file_diff_unstaged(&file_path, &options)
+                .map_err(|e| {
+                    error!("Failed to get unstaged diff: {:?}", e);
+                    format!("Could not retrieve unstaged diff: {}", e)
+                })
+        })
+        .await?;
 
     info!("Unstaged diff retrieved successfully");
     Ok(diff)
@@ -38,25 +35,23 @@ pub async fn get_file_diff_unstaged(
 #[instrument(skip(state))]
 pub async fn get_file_diff_staged(
     file_path: String,
+    options: Option<DiffRequestOptions>,
+    repo_id: Option<String>,
     state: State<'_, GitState>,
 ) -> Result<FileDiff, String> {
     info!(file_path, "Getting staged diff");
 
-    let current = state.current_repo.lock().unwrap();
-    let path = current.as_ref().ok_or_else(|| {
-        error!("No repository is currently open");
-        "No repository is currently open".to_string()
-    })?;
-
-    let engine = GitEngine::open(path).map_err(|e| {
-        error!("Failed to open repository: {:?}", e);
-        format!("Could not access repository: {}", e)
-    })?;
-
-    let diff = engine.get_file_diff_staged(&file_path).map_err(|e| {
-        error!("Failed to get staged diff: {:?}", e);
-        format!("Could not retrieve staged diff: {}", e)
-    })?;
+    let options = options.unwrap_or_default();
+    let diff = state
+        .run(repo_id.as_deref(), move |engine| {
+            engine
+                .get_file_diff_staged(&file_path, &options)
+                .map_err(|e| {
+                    error!("Failed to get staged diff: {:?}", e);
+                    format!("Could not retrieve staged diff: {}", e)
+                })
+        })
+        .await?;
 
     info!("Staged diff retrieved successfully");
     Ok(diff)
@@ -68,28 +63,53 @@ pub async fn get_file_diff_staged(
 pub async fn get_diff_between_commits(
     commit1: String,
     commit2: String,
+    options: Option<DiffRequestOptions>,
+    repo_id: Option<String>,
     state: State<'_, GitState>,
 ) -> Result<Vec<FileDiff>, String> {
     info!(commit1, commit2, "Getting diff between commits");
 
-    let current = state.current_repo.lock().unwrap();
-    let path = current.as_ref().ok_or_else(|| {
-        error!("No repository is currently open");
-        "No repository is currently open".to_string()
-    })?;
-
-    let engine = GitEngine::open(path).map_err(|e| {
-        error!("Failed to open repository: {:?}", e);
-        format!("Could not access repository: {}", e)
-    })?;
-
-    let diffs = engine
-        .get_diff_between_commits(&commit1, &commit2)
-        .map_err(|e| {
-            error!("Failed to get diff: {:?}", e);
-            format!("Could not retrieve diff: {}", e)
-        })?;
+    let options = options.unwrap_or_default();
+    let diffs = state
+        .run(repo_id.as_deref(), move |engine| {
+            engine
+                .get_diff_between_commits(&commit1, &commit2, &options)
+                .map_err(|e| {
+                    error!("Failed to get diff: {:?}", e);
+                    format!("Could not retrieve diff: {}", e)
+                })
+        })
+        .await?;
 
     info!("Diff between commits retrieved successfully");
     Ok(diffs)
 }
+
+/// Get every hunk of one file in a commit, or between two commits
+#[tauri::command]
+#[instrument(skip(state, options))]
+pub async fn get_commit_file_diff(
+    from: Option<String>,
+    to: String,
+    file_path: String,
+    options: Option<DiffRequestOptions>,
+    repo_id: Option<String>,
+    state: State<'_, GitState>,
+) -> Result<FileDiff, String> {
+    info!(to, file_path, "Getting full file diff");
+
+    let options = options.unwrap_or_default();
+    let diff = state
+        .run(repo_id.as_deref(), move |engine| {
+            engine
+                .get_commit_file_diff(from.as_deref(), &to, &file_path, &options)
+                .map_err(|e| {
+                    error!("Failed to get file diff: {:?}", e);
+                    format!("Could not retrieve file diff: {}", e)
+                })
+        })
+        .await?;
+
+    info!("Full file diff retrieved successfully");
+    Ok(diff)
+}
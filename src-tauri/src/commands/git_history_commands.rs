@@ -1,60 +1,113 @@
 use crate::commands::git_state::GitState;
-use crate::core::git_engine::GitEngine;
-use crate::models::git_repository::{Commit, CommitSummary, FileDiff};
+use crate::models::git_repository::{
+    BlameInfo, Commit, CommitGraphPage, CommitSearchQuery, CommitSummary, FileDiff,
+    FileHistoryEntry, GraphLayoutState, HistoryRange, MergeResult,
+};
 use tauri::State;
 use tracing::{error, info, instrument};
 
-/// Get paginated commits
+/// Get paginated commits, from HEAD unless a revision range is given
 #[tauri::command]
 #[instrument(skip(state))]
 pub async fn get_commits(
     limit: usize,
     offset: usize,
+    range: Option<HistoryRange>,
+    repo_id: Option<String>,
     state: State<'_, GitState>,
 ) -> Result<Vec<CommitSummary>, String> {
     info!(limit, offset, "Getting commits");
 
-    let current = state.current_repo.lock().unwrap();
-    let path = current.as_ref().ok_or_else(|| {
-        error!("No repository is currently open");
-        "No repository is currently open".to_string()
-    })?;
+    let range = range.unwrap_or_default();
+    let commits = state
+        .run(repo_id.as_deref(), move |engine| {
+            engine.get_commits(limit, offset, &range).map_err(|e| {
+                error!("Failed to get commits: {:?}", e);
+                format!("Could not retrieve commits: {}", e)
+            })
+        })
+        .await?;
 
-    let engine = GitEngine::open(path).map_err(|e| {
-        error!("Failed to open repository: {:?}", e);
-        format!("Could not access repository: {}", e)
-    })?;
+    info!(count = commits.len(), "Commits retrieved successfully");
+    Ok(commits)
+}
 
-    let commits = engine.get_commits(limit, offset).map_err(|e| {
-        error!("Failed to get commits: {:?}", e);
-        format!("Could not retrieve commits: {}", e)
-    })?;
+/// Get a page of the commit graph, continuing the lanes of the previous page
+#[tauri::command]
+#[instrument(skip(state, layout))]
+pub async fn get_commit_graph(
+    limit: usize,
+    offset: usize,
+    layout: Option<GraphLayoutState>,
+    repo_id: Option<String>,
+    state: State<'_, GitState>,
+) -> Result<CommitGraphPage, String> {
+    info!(limit, offset, "Getting commit graph");
+
+    let page = state
+        .run(repo_id.as_deref(), move |engine| {
+            engine
+                .get_commit_graph(limit, offset, layout.as_ref())
+                .map_err(|e| {
+                    error!("Failed to get commit graph: {:?}", e);
+                    format!("Could not retrieve commit graph: {}", e)
+                })
+        })
+        .await?;
+
+    info!(
+        count = page.rows.len(),
+        "Commit graph retrieved successfully"
+    );
+    Ok(page)
+}
 
-    info!(count = commits.len(), "Commits retrieved successfully");
+/// Search commit history by message, author, date, path and content
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn search_commits(
+    query: CommitSearchQuery,
+    limit: usize,
+    offset: usize,
+    repo_id: Option<String>,
+    state: State<'_, GitState>,
+) -> Result<Vec<CommitSummary>, String> {
+    info!(limit, offset, "Searching commits");
+
+    let commits = state
+        .run(repo_id.as_deref(), move |engine| {
+            engine.search_commits(&query, limit, offset).map_err(|e| {
+                error!("Failed to search commits: {:?}", e);
+                format!("Could not search commits: {}", e)
+            })
+        })
+        .await?;
+
+    info!(
+        count = commits.len(),
+        "Commit search completed successfully"
+    );
     Ok(commits)
 }
 
 /// Get commit details
 #[tauri::command]
 #[instrument(skip(state))]
-pub async fn get_commit_details(sha: String, state: State<'_, GitState>) -> Result<Commit, String> {
+pub async fn get_commit_details(
+    sha: String,
+    repo_id: Option<String>,
+    state: State<'_, GitState>,
+) -> Result<Commit, String> {
     info!(sha, "Getting commit details");
 
-    let current = state.current_repo.lock().unwrap();
-    let path = current.as_ref().ok_or_else(|| {
-        error!("No repository is currently open");
-        "No repository is currently open".to_string()
-    })?;
-
-    let engine = GitEngine::open(path).map_err(|e| {
-        error!("Failed to open repository: {:?}", e);
-        format!("Could not access repository: {}", e)
-    })?;
-
-    let commit = engine.get_commit_details(&sha).map_err(|e| {
-        error!("Failed to get commit details: {:?}", e);
-        format!("Could not retrieve commit details: {}", e)
-    })?;
+    let commit = state
+        .run(repo_id.as_deref(), move |engine| {
+            engine.get_commit_details(&sha).map_err(|e| {
+                error!("Failed to get commit details: {:?}", e);
+                format!("Could not retrieve commit details: {}", e)
+            })
+        })
+        .await?;
 
     info!("Commit details retrieved successfully");
     Ok(commit)
@@ -65,56 +118,132 @@ pub async fn get_commit_details(sha: String, state: State<'_, GitState>) -> Resu
 #[instrument(skip(state))]
 pub async fn get_commit_diff(
     sha: String,
+    repo_id: Option<String>,
     state: State<'_, GitState>,
 ) -> Result<Vec<FileDiff>, String> {
     info!(sha, "Getting commit diff");
 
-    let current = state.current_repo.lock().unwrap();
-    let path = current.as_ref().ok_or_else(|| {
-        error!("No repository is currently open");
-        "No repository is currently open".to_string()
-    })?;
-
-    let engine = GitEngine::open(path).map_err(|e| {
-        error!("Failed to open repository: {:?}", e);
-        format!("Could not access repository: {}", e)
-    })?;
-
-    let diffs = engine.get_commit_diff(&sha).map_err(|e| {
-        error!("Failed to get commit diff: {:?}", e);
-        format!("Could not retrieve commit diff: {}", e)
-    })?;
+    let diffs = state
+        .run(repo_id.as_deref(), move |engine| {
+            engine.get_commit_diff(&sha).map_err(|e| {
+                error!("Failed to get commit diff: {:?}", e);
+                format!("Could not retrieve commit diff: {}", e)
+            })
+        })
+        .await?;
 
     info!("Commit diff retrieved successfully");
     Ok(diffs)
 }
 
-/// Get file history
+/// Get the commits that changed a file, following renames
 #[tauri::command]
 #[instrument(skip(state))]
 pub async fn get_file_history(
     file_path: String,
     limit: usize,
+    range: Option<HistoryRange>,
+    repo_id: Option<String>,
     state: State<'_, GitState>,
-) -> Result<Vec<CommitSummary>, String> {
+) -> Result<Vec<FileHistoryEntry>, String> {
     info!(file_path, limit, "Getting file history");
 
-    let current = state.current_repo.lock().unwrap();
-    let path = current.as_ref().ok_or_else(|| {
-        error!("No repository is currently open");
-        "No repository is currently open".to_string()
-    })?;
-
-    let engine = GitEngine::open(path).map_err(|e| {
-        error!("Failed to open repository: {:?}", e);
-        format!("Could not access repository: {}", e)
-    })?;
-
-    let commits = engine.get_file_history(&file_path, limit).map_err(|e| {
-        error!("Failed to get file history: {:?}", e);
-        format!("Could not retrieve file history: {}", e)
-    })?;
+    let range = range.unwrap_or_default();
+    let commits = state
+        .run(repo_id.as_deref(), move |engine| {
+            engine
+                .get_file_history(&file_path, limit, &range)
+                .map_err(|e| {
+                    error!("Failed to get file history: {:?}", e);
+                    format!("Could not retrieve file history: {}", e)
+                })
+        })
+        .await?;
 
     info!(count = commits.len(), "File history retrieved successfully");
     Ok(commits)
 }
+
+/// Get line-by-line blame for a file
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn get_file_blame(
+    file_path: String,
+    revision: Option<String>,
+    ignore_whitespace: Option<bool>,
+    follow_moves: Option<bool>,
+    repo_id: Option<String>,
+    state: State<'_, GitState>,
+) -> Result<BlameInfo, String> {
+    info!(file_path, ?revision, "Getting file blame");
+
+    let blame = state
+        .run(repo_id.as_deref(), move |engine| {
+            engine
+                .get_file_blame(
+                    &file_path,
+                    revision.as_deref(),
+                    ignore_whitespace.unwrap_or(false),
+                    follow_moves.unwrap_or(false),
+                )
+                .map_err(|e| {
+                    error!("Failed to get file blame: {:?}", e);
+                    format!("Could not retrieve blame for '{}': {}", file_path, e)
+                })
+        })
+        .await?;
+
+    info!(
+        lines = blame.lines.len(),
+        "File blame retrieved successfully"
+    );
+    Ok(blame)
+}
+
+/// Cherry-pick one or more commits onto the current branch
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn cherry_pick_commits(
+    shas: Vec<String>,
+    mainline: Option<u32>,
+    repo_id: Option<String>,
+    state: State<'_, GitState>,
+) -> Result<MergeResult, String> {
+    info!(count = shas.len(), "Cherry-picking commits");
+
+    let result = state
+        .run(repo_id.as_deref(), move |engine| {
+            engine.cherry_pick_commits(&shas, mainline).map_err(|e| {
+                error!("Failed to cherry-pick commits: {:?}", e);
+                format!("Could not cherry-pick commits: {}", e)
+            })
+        })
+        .await?;
+
+    info!(success = result.success, "Cherry-pick finished");
+    Ok(result)
+}
+
+/// Revert one or more commits on the current branch
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn revert_commits(
+    shas: Vec<String>,
+    mainline: Option<u32>,
+    repo_id: Option<String>,
+    state: State<'_, GitState>,
+) -> Result<MergeResult, String> {
+    info!(count = shas.len(), "Reverting commits");
+
+    let result = state
+        .run(repo_id.as_deref(), move |engine| {
+            engine.revert_commits(&shas, mainline).map_err(|e| {
+                error!("Failed to revert commits: {:?}", e);
+                format!("Could not revert commits: {}", e)
+            })
+        })
+        .await?;
+
+    info!(success = result.success, "Revert finished");
+    Ok(result)
+}
@@ -0,0 +1,137 @@
+use crate::commands::git_state::GitState;
+use crate::models::git_repository::{
+    ConflictResolution, ConflictVersions, MergeMode, MergeResult, MergeStrategy, ResetResult,
+};
+use tauri::State;
+use tracing::{error, info, instrument};
+
+/// Merge a branch into the current branch
+#[tauri::command]
+#[instrument(skip(state, message))]
+pub async fn merge_branch(
+    branch: String,
+    mode: Option<MergeMode>,
+    strategy: Option<MergeStrategy>,
+    message: Option<String>,
+    repo_id: Option<String>,
+    state: State<'_, GitState>,
+) -> Result<MergeResult, String> {
+    info!("Merging branch");
+
+    let result = state
+        .run(repo_id.as_deref(), move |engine| {
+            engine
+                .merge_branch(
+                    &branch,
+                    mode.unwrap_or(MergeMode::FastForward),
+                    strategy,
+                    message.as_deref(),
+                )
+                .map_err(|e| {
+                    error!("Failed to merge branch: {:?}", e);
+                    format!("Could not merge branch '{}': {}", branch, e)
+                })
+        })
+        .await?;
+
+    info!(
+        success = result.success,
+        conflicts = result.conflicts.len(),
+        "Merge finished"
+    );
+    Ok(result)
+}
+
+/// Get the ancestor, ours and theirs versions of a conflicted file
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn get_conflict_versions(
+    file_path: String,
+    repo_id: Option<String>,
+    state: State<'_, GitState>,
+) -> Result<ConflictVersions, String> {
+    info!("Getting conflict versions");
+
+    let versions = state
+        .run(repo_id.as_deref(), move |engine| {
+            engine.get_conflict_versions(&file_path).map_err(|e| {
+                error!("Failed to get conflict versions: {:?}", e);
+                format!("Could not read conflict for '{}': {}", file_path, e)
+            })
+        })
+        .await?;
+
+    info!("Conflict versions retrieved successfully");
+    Ok(versions)
+}
+
+/// Resolve a conflicted file and mark it as resolved
+#[tauri::command]
+#[instrument(skip(state, resolution), fields(path = %resolution.path))]
+pub async fn resolve_conflict(
+    resolution: ConflictResolution,
+    repo_id: Option<String>,
+    state: State<'_, GitState>,
+) -> Result<(), String> {
+    info!("Resolving conflict");
+
+    state
+        .run(repo_id.as_deref(), move |engine| {
+            engine.resolve_conflict(&resolution).map_err(|e| {
+                error!("Failed to resolve conflict: {:?}", e);
+                format!("Could not resolve conflict in '{}': {}", resolution.path, e)
+            })
+        })
+        .await?;
+
+    info!("Conflict resolved successfully");
+    Ok(())
+}
+
+/// Abort the merge in progress, backing up the commit and uncommitted changes
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn abort_merge(
+    repo_id: Option<String>,
+    state: State<'_, GitState>,
+) -> Result<ResetResult, String> {
+    info!("Aborting merge");
+
+    let result = state
+        .run(repo_id.as_deref(), move |engine| {
+            engine.abort_merge().map_err(|e| {
+                error!("Failed to abort merge: {:?}", e);
+                format!("Could not abort merge: {}", e)
+            })
+        })
+        .await?;
+
+    info!(
+        stashed = result.stash_sha.is_some(),
+        "Merge aborted successfully"
+    );
+    Ok(result)
+}
+
+/// Commit the merge in progress once all conflicts are resolved
+#[tauri::command]
+#[instrument(skip(state, message))]
+pub async fn continue_merge(
+    message: Option<String>,
+    repo_id: Option<String>,
+    state: State<'_, GitState>,
+) -> Result<String, String> {
+    info!("Continuing merge");
+
+    let commit_hash = state
+        .run(repo_id.as_deref(), move |engine| {
+            engine.continue_merge(message.as_deref()).map_err(|e| {
+                error!("Failed to continue merge: {:?}", e);
+                format!("Could not complete merge: {}", e)
+            })
+        })
+        .await?;
+
+    info!(commit_hash = %commit_hash, "Merge completed successfully");
+    Ok(commit_hash)
+}
@@ -0,0 +1,141 @@
+use crate::commands::git_state::GitState;
+use crate::models::git_repository::{CommitSummary, RebasePlanStep, RebaseStatus};
+use tauri::State;
+use tracing::{error, info, instrument};
+
+/// List the commits an interactive rebase onto `onto` would replay, oldest first
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn get_rebase_todo(
+    onto: String,
+    repo_id: Option<String>,
+    state: State<'_, GitState>,
+) -> Result<Vec<CommitSummary>, String> {
+    info!(onto, "Getting rebase todo list");
+
+    let commits = state
+        .run(repo_id.as_deref(), move |engine| {
+            engine.get_rebase_todo(&onto).map_err(|e| {
+                error!("Failed to get rebase todo: {:?}", e);
+                format!("Could not list commits to rebase: {}", e)
+            })
+        })
+        .await?;
+
+    info!(
+        count = commits.len(),
+        "Rebase todo list retrieved successfully"
+    );
+    Ok(commits)
+}
+
+/// Start an interactive rebase that applies the given plan onto `onto`
+#[tauri::command]
+#[instrument(skip(state, plan), fields(steps = plan.len()))]
+pub async fn start_interactive_rebase(
+    onto: String,
+    plan: Vec<RebasePlanStep>,
+    repo_id: Option<String>,
+    state: State<'_, GitState>,
+) -> Result<RebaseStatus, String> {
+    info!(onto, "Starting interactive rebase");
+
+    let status = state
+        .run(repo_id.as_deref(), move |engine| {
+            engine.start_interactive_rebase(&onto, plan).map_err(|e| {
+                error!("Failed to start interactive rebase: {:?}", e);
+                format!("Could not start rebase: {}", e)
+            })
+        })
+        .await?;
+
+    info!(
+        in_progress = status.in_progress,
+        "Interactive rebase started"
+    );
+    Ok(status)
+}
+
+/// Continue the rebase in progress after resolving conflicts
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn continue_rebase(
+    repo_id: Option<String>,
+    state: State<'_, GitState>,
+) -> Result<RebaseStatus, String> {
+    info!("Continuing rebase");
+
+    let status = state
+        .run(repo_id.as_deref(), move |engine| {
+            engine.continue_rebase().map_err(|e| {
+                error!("Failed to continue rebase: {:?}", e);
+                format!("Could not continue rebase: {}", e)
+            })
+        })
+        .await?;
+
+    info!(in_progress = status.in_progress, "Rebase continued");
+    Ok(status)
+}
+
+/// Skip the current rebase step
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn skip_rebase_step(
+    repo_id: Option<String>,
+    state: State<'_, GitState>,
+) -> Result<RebaseStatus, String> {
+    info!("Skipping rebase step");
+
+    let status = state
+        .run(repo_id.as_deref(), move |engine| {
+            engine.skip_rebase_step().map_err(|e| {
+                error!("Failed to skip rebase step: {:?}", e);
+                format!("Could not skip rebase step: {}", e)
+            })
+        })
+        .await?;
+
+    info!(in_progress = status.in_progress, "Rebase step skipped");
+    Ok(status)
+}
+
+/// Abort the rebase in progress and restore the original branch
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn abort_rebase(
+    repo_id: Option<String>,
+    state: State<'_, GitState>,
+) -> Result<(), String> {
+    info!("Aborting rebase");
+
+    state
+        .run(repo_id.as_deref(), move |engine| {
+            engine.abort_rebase().map_err(|e| {
+                error!("Failed to abort rebase: {:?}", e);
+                format!("Could not abort rebase: {}", e)
+            })
+        })
+        .await?;
+
+    info!("Rebase aborted successfully");
+    Ok(())
+}
+
+/// Get the progress of the rebase in progress, if any
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn get_rebase_status(
+    repo_id: Option<String>,
+    state: State<'_, GitState>,
+) -> Result<RebaseStatus, String> {
+    let status = state
+        .run(repo_id.as_deref(), move |engine| {
+            engine.get_rebase_status().map_err(|e| {
+                error!("Failed to get rebase status: {:?}", e);
+                format!("Could not read rebase status: {}", e)
+            })
+        })
+        .await?;
+    Ok(status)
+}
@@ -0,0 +1,54 @@
+use crate::commands::git_state::GitState;
+use crate::models::git_repository::ReflogEntry;
+use tauri::State;
+use tracing::{error, info, instrument};
+
+/// Get the reflog for HEAD or a branch
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn get_reflog(
+    reference: Option<String>,
+    repo_id: Option<String>,
+    state: State<'_, GitState>,
+) -> Result<Vec<ReflogEntry>, String> {
+    info!(?reference, "Getting reflog");
+
+    let entries = state
+        .run(repo_id.as_deref(), move |engine| {
+            engine.get_reflog(reference.as_deref()).map_err(|e| {
+                error!("Failed to get reflog: {:?}", e);
+                format!("Could not retrieve reflog: {}", e)
+            })
+        })
+        .await?;
+
+    info!(count = entries.len(), "Reflog retrieved successfully");
+    Ok(entries)
+}
+
+/// Restore HEAD or a branch to a reflog entry, optionally stashing local changes first
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn restore_reflog_entry(
+    reference: Option<String>,
+    index: usize,
+    stash_changes: Option<bool>,
+    repo_id: Option<String>,
+    state: State<'_, GitState>,
+) -> Result<String, String> {
+    info!(?reference, index, "Restoring reflog entry");
+
+    let sha = state
+        .run(repo_id.as_deref(), move |engine| {
+            engine
+                .restore_reflog_entry(reference.as_deref(), index, stash_changes.unwrap_or(false))
+                .map_err(|e| {
+                    error!("Failed to restore reflog entry: {:?}", e);
+                    format!("Could not restore reflog entry: {}", e)
+                })
+        })
+        .await?;
+
+    info!(sha = %sha, "Reflog entry restored successfully");
+    Ok(sha)
+}
@@ -1,30 +1,25 @@
 use crate::commands::git_state::GitState;
-use crate::core::git_engine::GitEngine;
-use crate::models::git_repository::Remote;
-use tauri::State;
-use tracing::{error, info, instrument};
+use crate::models::git_repository::{MergeResult, PullStrategy, Remote};
+use tauri::{AppHandle, State};
+use tracing::{error, info, instrument, warn};
 
 /// List all remotes
 #[tauri::command]
 #[instrument(skip(state))]
-pub async fn list_remotes(state: State<'_, GitState>) -> Result<Vec<Remote>, String> {
+pub async fn list_remotes(
+    repo_id: Option<String>,
+    state: State<'_, GitState>,
+) -> Result<Vec<Remote>, String> {
     info!("Listing remotes");
 
-    let current = state.current_repo.lock().unwrap();
-    let path = current.as_ref().ok_or_else(|| {
-        error!("No repository is currently open");
-        "No repository is currently open".to_string()
-    })?;
-
-    let engine = GitEngine::open(path).map_err(|e| {
-        error!("Failed to open repository: {:?}", e);
-        format!("Could not access repository: {}", e)
-    })?;
-
-    let remotes = engine.list_remotes().map_err(|e| {
-        error!("Failed to list remotes: {:?}", e);
-        format!("Could not retrieve remotes: {}", e)
-    })?;
+    let remotes = state
+        .run(repo_id.as_deref(), move |engine| {
+            engine.list_remotes().map_err(|e| {
+                error!("Failed to list remotes: {:?}", e);
+                format!("Could not retrieve remotes: {}", e)
+            })
+        })
+        .await?;
 
     info!(count = remotes.len(), "Remotes listed successfully");
     Ok(remotes)
@@ -36,25 +31,19 @@ pub async fn list_remotes(state: State<'_, GitState>) -> Result<Vec<Remote>, Str
 pub async fn add_remote(
     name: String,
     url: String,
+    repo_id: Option<String>,
     state: State<'_, GitState>,
 ) -> Result<(), String> {
     info!(name, url, "Adding remote");
 
-    let current = state.current_repo.lock().unwrap();
-    let path = current.as_ref().ok_or_else(|| {
-        error!("No repository is currently open");
-        "No repository is currently open".to_string()
-    })?;
-
-    let engine = GitEngine::open(path).map_err(|e| {
-        error!("Failed to open repository: {:?}", e);
-        format!("Could not access repository: {}", e)
-    })?;
-
-    engine.add_remote(&name, &url).map_err(|e| {
-        error!("Failed to add remote: {:?}", e);
-        format!("Could not add remote: {}", e)
-    })?;
+    state
+        .run(repo_id.as_deref(), move |engine| {
+            engine.add_remote(&name, &url).map_err(|e| {
+                error!("Failed to add remote: {:?}", e);
+                format!("Could not add remote: {}", e)
+            })
+        })
+        .await?;
 
     info!("Remote added successfully");
     Ok(())
@@ -63,24 +52,21 @@ pub async fn add_remote(
 /// Remove a remote
 #[tauri::command]
 #[instrument(skip(state))]
-pub async fn remove_remote(name: String, state: State<'_, GitState>) -> Result<(), String> {
+pub async fn remove_remote(
+    name: String,
+    repo_id: Option<String>,
+    state: State<'_, GitState>,
+) -> Result<(), String> {
     info!(name, "Removing remote");
 
-    let current = state.current_repo.lock().unwrap();
-    let path = current.as_ref().ok_or_else(|| {
-        error!("No repository is currently open");
-        "No repository is currently open".to_string()
-    })?;
-
-    let engine = GitEngine::open(path).map_err(|e| {
-        error!("Failed to open repository: {:?}", e);
-        format!("Could not access repository: {}", e)
-    })?;
-
-    engine.remove_remote(&name).map_err(|e| {
-        error!("Failed to remove remote: {:?}", e);
-        format!("Could not remove remote: {}", e)
-    })?;
+    state
+        .run(repo_id.as_deref(), move |engine| {
+            engine.remove_remote(&name).map_err(|e| {
+                error!("Failed to remove remote: {:?}", e);
+                format!("Could not remove remote: {}", e)
+            })
+        })
+        .await?;
 
     info!("Remote removed successfully");
     Ok(())
@@ -88,25 +74,28 @@ pub async fn remove_remote(name: String, state: State<'_, GitState>) -> Result<(
 
 /// Fetch from a remote
 #[tauri::command]
-#[instrument(skip(state))]
-pub async fn fetch_remote(name: String, state: State<'_, GitState>) -> Result<(), String> {
+#[instrument(skip(app, state))]
+pub async fn fetch_remote(
+    name: String,
+    operation_id: Option<String>,
+    app: AppHandle,
+    repo_id: Option<String>,
+    state: State<'_, GitState>,
+) -> Result<(), String> {
     info!(name, "Fetching from remote");
 
-    let current = state.current_repo.lock().unwrap();
-    let path = current.as_ref().ok_or_else(|| {
-        error!("No repository is currently open");
-        "No repository is currently open".to_string()
-    })?;
-
-    let engine = GitEngine::open(path).map_err(|e| {
-        error!("Failed to open repository: {:?}", e);
-        format!("Could not access repository: {}", e)
-    })?;
-
-    engine.fetch(&name).map_err(|e| {
-        error!("Failed to fetch: {:?}", e);
-        format!("Could not fetch from remote: {}", e)
-    })?;
+    let progress = state.begin_operation(&app, operation_id, "fetch")?;
+    let operation_id = progress.id().to_string();
+    let result = state
+        .run_standalone(repo_id.as_deref(), move |engine| {
+            engine.fetch(&name, Some(&progress)).map_err(|e| {
+                error!("Failed to fetch: {:?}", e);
+                format!("Could not fetch from remote: {}", e)
+            })
+        })
+        .await;
+    state.finish_operation(&operation_id);
+    result?;
 
     info!("Fetch completed successfully");
     Ok(())
@@ -114,32 +103,32 @@ pub async fn fetch_remote(name: String, state: State<'_, GitState>) -> Result<()
 
 /// Push to a remote
 #[tauri::command]
-#[instrument(skip(state))]
+#[instrument(skip(app, state))]
 pub async fn push_to_remote(
     remote: String,
     branch: String,
     force: Option<bool>,
+    operation_id: Option<String>,
+    app: AppHandle,
+    repo_id: Option<String>,
     state: State<'_, GitState>,
 ) -> Result<(), String> {
     info!(remote, branch, force, "Pushing to remote");
 
-    let current = state.current_repo.lock().unwrap();
-    let path = current.as_ref().ok_or_else(|| {
-        error!("No repository is currently open");
-        "No repository is currently open".to_string()
-    })?;
-
-    let engine = GitEngine::open(path).map_err(|e| {
-        error!("Failed to open repository: {:?}", e);
-        format!("Could not access repository: {}", e)
-    })?;
-
-    engine
-        .push(&remote, &branch, force.unwrap_or(false))
-        .map_err(|e| {
-            error!("Failed to push: {:?}", e);
-            format!("Could not push to remote: {}", e)
-        })?;
+    let progress = state.begin_operation(&app, operation_id, "push")?;
+    let operation_id = progress.id().to_string();
+    let result = state
+        .run_standalone(repo_id.as_deref(), move |engine| {
+            engine
+                .push(&remote, &branch, force.unwrap_or(false), Some(&progress))
+                .map_err(|e| {
+                    error!("Failed to push: {:?}", e);
+                    format!("Could not push to remote: {}", e)
+                })
+        })
+        .await;
+    state.finish_operation(&operation_id);
+    result?;
 
     info!("Push completed successfully");
     Ok(())
@@ -147,30 +136,67 @@ pub async fn push_to_remote(
 
 /// Pull from a remote
 #[tauri::command]
-#[instrument(skip(state))]
+#[instrument(skip(app, state))]
 pub async fn pull_from_remote(
     remote: String,
     branch: String,
+    strategy: Option<PullStrategy>,
+    operation_id: Option<String>,
+    app: AppHandle,
+    repo_id: Option<String>,
     state: State<'_, GitState>,
-) -> Result<(), String> {
-    info!(remote, branch, "Pulling from remote");
-
-    let current = state.current_repo.lock().unwrap();
-    let path = current.as_ref().ok_or_else(|| {
-        error!("No repository is currently open");
-        "No repository is currently open".to_string()
-    })?;
+) -> Result<MergeResult, String> {
+    info!(remote, branch, ?strategy, "Pulling from remote");
+
+    // Only the download runs on an engine of its own; the merge or rebase
+    // changes the working tree, so it waits its turn on the repository session
+    let progress = state.begin_operation(&app, operation_id, "pull")?;
+    let operation_id = progress.id().to_string();
+    let fetch_remote = remote.clone();
+    let fetched = state
+        .run_standalone(repo_id.as_deref(), move |engine| {
+            engine.fetch(&fetch_remote, Some(&progress)).map_err(|e| {
+                error!("Failed to fetch for pull: {:?}", e);
+                format!("Could not pull from remote: {}", e)
+            })
+        })
+        .await;
+    state.finish_operation(&operation_id);
+    fetched?;
+
+    let result = state
+        .run(repo_id.as_deref(), move |engine| {
+            engine
+                .integrate_upstream(&remote, &branch, strategy.unwrap_or(PullStrategy::Merge))
+                .map_err(|e| {
+                    error!("Failed to pull: {:?}", e);
+                    format!("Could not pull from remote: {}", e)
+                })
+        })
+        .await?;
+
+    info!(
+        success = result.success,
+        conflicts = result.conflicts.len(),
+        "Pull completed"
+    );
+    Ok(result)
+}
 
-    let engine = GitEngine::open(path).map_err(|e| {
-        error!("Failed to open repository: {:?}", e);
-        format!("Could not access repository: {}", e)
-    })?;
+/// Cancel a running fetch, push or pull by its operation id
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn cancel_operation(
+    operation_id: String,
+    state: State<'_, GitState>,
+) -> Result<(), String> {
+    info!("Cancelling operation");
 
-    engine.pull(&remote, &branch).map_err(|e| {
-        error!("Failed to pull: {:?}", e);
-        format!("Could not pull from remote: {}", e)
-    })?;
+    if !state.cancel_operation(&operation_id) {
+        warn!("No running operation with this id");
+        return Err(format!("No running operation '{}'", operation_id));
+    }
 
-    info!("Pull completed successfully");
+    info!("Cancellation requested");
     Ok(())
 }
@@ -1,34 +1,90 @@
-use crate::commands::git_state::GitState;
+use crate::commands::git_state::{run_blocking, GitState};
 use crate::core::git_engine::GitEngine;
 use crate::models::git_repository::*;
-use std::path::PathBuf;
-use tauri::State;
+use std::path::Path;
+use tauri::{AppHandle, Manager, State};
 use tracing::{error, info, instrument, warn};
 
 /// Open a repository
 #[tauri::command]
-#[instrument(skip(state), fields(path = %path))]
-pub async fn open_repository(
-    path: String,
+#[instrument(skip(app), fields(path = %path))]
+pub async fn open_repository(path: String, app: AppHandle) -> Result<RepositoryInfo, String> {
+    info!("Opening repository");
+
+    let info = run_blocking(move || open_and_register(&app, Path::new(&path))).await?;
+
+    info!("Repository opened successfully");
+    Ok(info)
+}
+
+/// Clone a repository and make it the current repository
+#[tauri::command]
+#[instrument(skip(app, state, options), fields(url = %options.url, destination = %options.destination))]
+pub async fn clone_repository(
+    options: CloneOptions,
+    operation_id: Option<String>,
+    app: AppHandle,
     state: State<'_, GitState>,
 ) -> Result<RepositoryInfo, String> {
-    info!("Opening repository");
+    info!("Cloning repository");
+
+    let progress = state.begin_operation(&app, operation_id, "clone")?;
+    let operation_id = progress.id().to_string();
+    let result = run_blocking(move || {
+        let engine = GitEngine::clone_repository(&options, Some(&progress)).map_err(|e| {
+            error!("Failed to clone repository: {:?}", e);
+            format!("Could not clone '{}': {}", options.url, e)
+        })?;
 
-    let engine = GitEngine::open(&path).map_err(|e| {
-        error!("Failed to open repository: {:?}", e);
-        format!("Could not open repository: {}", e)
-    })?;
+        let mut info = engine.get_info().map_err(|e| {
+            error!("Failed to get repository info: {:?}", e);
+            format!("Could not read repository information: {}", e)
+        })?;
 
-    let info = engine.get_info().map_err(|e| {
-        error!("Failed to get repository info: {:?}", e);
-        format!("Could not read repository information: {}", e)
-    })?;
+        info.id = Some(app.state::<GitState>().register_repository(&app, engine));
+        Ok(info)
+    })
+    .await;
+    state.finish_operation(&operation_id);
+    let info = result?;
 
-    // Store current repository path
-    let mut current = state.current_repo.lock().unwrap();
-    *current = Some(PathBuf::from(path));
+    info!("Repository cloned successfully");
+    Ok(info)
+}
 
-    info!("Repository opened successfully");
+/// Initialize a new repository and make it the current repository
+#[tauri::command]
+#[instrument(skip(app, first_commit_message), fields(path = %path))]
+pub async fn init_repository(
+    path: String,
+    initial_branch: Option<String>,
+    first_commit_message: Option<String>,
+    app: AppHandle,
+) -> Result<RepositoryInfo, String> {
+    info!("Initializing repository");
+
+    let info = run_blocking(move || {
+        let engine = GitEngine::init_repository(
+            &path,
+            initial_branch.as_deref(),
+            first_commit_message.as_deref(),
+        )
+        .map_err(|e| {
+            error!("Failed to initialize repository: {:?}", e);
+            format!("Could not initialize repository: {}", e)
+        })?;
+
+        let mut info = engine.get_info().map_err(|e| {
+            error!("Failed to get repository info: {:?}", e);
+            format!("Could not read repository information: {}", e)
+        })?;
+
+        info.id = Some(app.state::<GitState>().register_repository(&app, engine));
+        Ok(info)
+    })
+    .await?;
+
+    info!("Repository initialized successfully");
     Ok(info)
 }
 
@@ -38,15 +94,18 @@ pub async fn open_repository(
 pub async fn discover_repository(path: String) -> Result<RepositoryInfo, String> {
     info!("Discovering repository");
 
-    let engine = GitEngine::discover(&path).map_err(|e| {
-        error!("Failed to discover repository: {:?}", e);
-        format!("No git repository found at or above: {}", path)
-    })?;
+    let info = run_blocking(move || {
+        let engine = GitEngine::discover(&path).map_err(|e| {
+            error!("Failed to discover repository: {:?}", e);
+            format!("No git repository found at or above: {}", path)
+        })?;
 
-    let info = engine.get_info().map_err(|e| {
-        error!("Failed to get repository info: {:?}", e);
-        format!("Could not read repository information: {}", e)
-    })?;
+        engine.get_info().map_err(|e| {
+            error!("Failed to get repository info: {:?}", e);
+            format!("Could not read repository information: {}", e)
+        })
+    })
+    .await?;
 
     info!("Repository discovered successfully");
     Ok(info)
@@ -55,82 +114,119 @@ pub async fn discover_repository(path: String) -> Result<RepositoryInfo, String>
 /// Get repository status
 #[tauri::command]
 #[instrument(skip(state))]
-pub async fn get_repository_status(state: State<'_, GitState>) -> Result<RepositoryStatus, String> {
-    let current = state.current_repo.lock().unwrap();
-    let path = current.as_ref().ok_or_else(|| {
-        warn!("No repository is currently open");
-        "No repository is currently open".to_string()
-    })?;
-
-    let engine = GitEngine::open(path).map_err(|e| {
-        error!("Failed to open repository: {:?}", e);
-        format!("Could not access repository: {}", e)
-    })?;
-
-    let status = engine.get_status().map_err(|e| {
-        error!("Failed to get status: {:?}", e);
-        format!("Could not read repository status: {}", e)
-    })?;
+pub async fn get_repository_status(
+    include_ignored: Option<bool>,
+    repo_id: Option<String>,
+    state: State<'_, GitState>,
+) -> Result<RepositoryStatus, String> {
+    let status = state
+        .run(repo_id.as_deref(), move |engine| {
+            engine
+                .get_status(include_ignored.unwrap_or(false))
+                .map_err(|e| {
+                    error!("Failed to get status: {:?}", e);
+                    format!("Could not read repository status: {}", e)
+                })
+        })
+        .await?;
     Ok(status)
 }
 
 /// Get branches
 #[tauri::command]
 #[instrument(skip(state))]
-pub async fn get_branches(state: State<'_, GitState>) -> Result<Vec<Branch>, String> {
+pub async fn get_branches(
+    repo_id: Option<String>,
+    state: State<'_, GitState>,
+) -> Result<Vec<Branch>, String> {
     info!("Getting branches");
 
-    let current = state.current_repo.lock().unwrap();
-    let path = current.as_ref().ok_or_else(|| {
-        warn!("No repository is currently open");
-        "No repository is currently open".to_string()
-    })?;
-
-    let engine = GitEngine::open(path).map_err(|e| {
-        error!("Failed to open repository: {:?}", e);
-        format!("Could not access repository: {}", e)
-    })?;
-
-    let branches = engine.get_branches().map_err(|e| {
-        error!("Failed to get branches: {:?}", e);
-        format!("Could not read branches: {}", e)
-    })?;
+    let branches = state
+        .run(repo_id.as_deref(), move |engine| {
+            engine.get_branches().map_err(|e| {
+                error!("Failed to get branches: {:?}", e);
+                format!("Could not read branches: {}", e)
+            })
+        })
+        .await?;
 
     info!("Branches retrieved successfully");
     Ok(branches)
 }
 
-/// Get current repository path
+/// Get the path of the active repository
 #[tauri::command]
 #[instrument(skip(state))]
 pub async fn get_current_repository(state: State<'_, GitState>) -> Result<Option<String>, String> {
-    let current = state.current_repo.lock().unwrap();
-    let path = current.as_ref().map(|p| p.to_string_lossy().to_string());
+    let active = state.active_repo.lock().unwrap().clone();
+    let path = active
+        .and_then(|id| state.repository_path(Some(&id)).ok())
+        .map(|p| p.to_string_lossy().to_string());
     info!(has_repo = path.is_some(), "Current repository check");
     Ok(path)
 }
 
-/// Get git configuration
+/// List the repositories open in the app
 #[tauri::command]
 #[instrument(skip(state))]
-pub async fn get_git_config(state: State<'_, GitState>) -> Result<(String, String), String> {
-    info!("Getting git configuration");
+pub async fn list_open_repositories(
+    state: State<'_, GitState>,
+) -> Result<Vec<OpenRepositoryInfo>, String> {
+    let repositories = state.list_repositories();
+    info!(count = repositories.len(), "Open repositories listed");
+    Ok(repositories)
+}
 
-    let current = state.current_repo.lock().unwrap();
-    let path = current.as_ref().ok_or_else(|| {
-        warn!("No repository is currently open");
-        "No repository is currently open".to_string()
-    })?;
+/// Make an open repository the one commands use when not given a repository id
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn set_active_repository(
+    repo_id: String,
+    state: State<'_, GitState>,
+) -> Result<(), String> {
+    info!("Switching active repository");
 
-    let engine = GitEngine::open(path).map_err(|e| {
-        error!("Failed to open repository: {:?}", e);
-        format!("Could not access repository: {}", e)
-    })?;
+    if !state.set_active_repository(&repo_id) {
+        warn!("Repository is not open");
+        return Err(format!("Repository '{}' is not open", repo_id));
+    }
 
-    let config = engine.get_config().map_err(|e| {
-        error!("Failed to get config: {:?}", e);
-        format!("Could not read git configuration: {}", e)
-    })?;
+    info!("Active repository switched");
+    Ok(())
+}
+
+/// Close a repository, stopping its watcher
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn close_repository(repo_id: String, state: State<'_, GitState>) -> Result<(), String> {
+    info!("Closing repository");
+
+    if !state.close_repository(&repo_id) {
+        warn!("Repository is not open");
+        return Err(format!("Repository '{}' is not open", repo_id));
+    }
+
+    info!("Repository closed successfully");
+    Ok(())
+}
+
+/// Get git configuration
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn get_git_config(
+    repo_id: Option<String>,
+    state: State<'_, GitState>,
+) -> Result<(String, String), String> {
+    info!("Getting git configuration");
+
+    let config = state
+        .run(repo_id.as_deref(), move |engine| {
+            engine.get_config().map_err(|e| {
+                error!("Failed to get config: {:?}", e);
+                format!("Could not read git configuration: {}", e)
+            })
+        })
+        .await?;
 
     info!("Git configuration retrieved successfully");
     Ok(config)
@@ -139,25 +235,19 @@ pub async fn get_git_config(state: State<'_, GitState>) -> Result<(String, Strin
 #[tauri::command]
 #[instrument(skip(state))]
 pub async fn get_git_config_detailed(
+    repo_id: Option<String>,
     state: State<'_, GitState>,
 ) -> Result<(String, String, String, String), String> {
     info!("Getting detailed git configuration");
 
-    let current = state.current_repo.lock().unwrap();
-    let path = current.as_ref().ok_or_else(|| {
-        warn!("No repository is currently open");
-        "No repository is currently open".to_string()
-    })?;
-
-    let engine = GitEngine::open(path).map_err(|e| {
-        error!("Failed to open repository: {:?}", e);
-        format!("Could not access repository: {}", e)
-    })?;
-
-    let config = engine.get_config_detailed().map_err(|e| {
-        error!("Failed to get detailed config: {:?}", e);
-        format!("Could not read detailed git configuration: {}", e)
-    })?;
+    let config = state
+        .run(repo_id.as_deref(), move |engine| {
+            engine.get_config_detailed().map_err(|e| {
+                error!("Failed to get detailed config: {:?}", e);
+                format!("Could not read detailed git configuration: {}", e)
+            })
+        })
+        .await?;
 
     info!("Detailed git configuration retrieved successfully");
     Ok(config)
@@ -169,28 +259,41 @@ pub async fn set_git_config(
     name: String,
     email: String,
     global: Option<bool>,
+    repo_id: Option<String>,
     state: State<'_, GitState>,
 ) -> Result<(), String> {
     info!("Setting git configuration");
 
-    let current = state.current_repo.lock().unwrap();
-    let path = current.as_ref().ok_or_else(|| {
-        warn!("No repository is currently open");
-        "No repository is currently open".to_string()
-    })?;
+    state
+        .run(repo_id.as_deref(), move |engine| {
+            engine
+                .set_config(&name, &email, global.unwrap_or(false))
+                .map_err(|e| {
+                    error!("Failed to set config: {:?}", e);
+                    format!("Could not update git configuration: {}", e)
+                })
+        })
+        .await?;
 
+    info!("Git configuration updated successfully");
+    Ok(())
+}
+
+// Helper functions
+
+/// Open a repository, register it and make it the active one. Blocks, so call
+/// it from a worker thread.
+pub(crate) fn open_and_register(app: &AppHandle, path: &Path) -> Result<RepositoryInfo, String> {
     let engine = GitEngine::open(path).map_err(|e| {
         error!("Failed to open repository: {:?}", e);
-        format!("Could not access repository: {}", e)
+        format!("Could not open repository: {}", e)
     })?;
 
-    engine
-        .set_config(&name, &email, global.unwrap_or(false))
-        .map_err(|e| {
-            error!("Failed to set config: {:?}", e);
-            format!("Could not update git configuration: {}", e)
-        })?;
+    let mut info = engine.get_info().map_err(|e| {
+        error!("Failed to get repository info: {:?}", e);
+        format!("Could not read repository information: {}", e)
+    })?;
 
-    info!("Git configuration updated successfully");
-    Ok(())
+    info.id = Some(app.state::<GitState>().register_repository(app, engine));
+    Ok(info)
 }
@@ -1,29 +1,28 @@
 use crate::commands::git_state::GitState;
-use crate::core::git_engine::GitEngine;
+use crate::models::git_repository::{
+    DiffHunk, DiscardResult, ResetMode, ResetResult, RestoreSource,
+};
 use tauri::State;
-use tracing::{error, info, instrument, warn};
+use tracing::{error, info, instrument};
 
 /// Stage a file
 #[tauri::command]
 #[instrument(skip(state), fields(file = %file_path))]
-pub async fn stage_file(file_path: String, state: State<'_, GitState>) -> Result<(), String> {
+pub async fn stage_file(
+    file_path: String,
+    repo_id: Option<String>,
+    state: State<'_, GitState>,
+) -> Result<(), String> {
     info!("Staging file");
 
-    let current = state.current_repo.lock().unwrap();
-    let path = current.as_ref().ok_or_else(|| {
-        warn!("No repository is currently open");
-        "No repository is currently open".to_string()
-    })?;
-
-    let engine = GitEngine::open(path).map_err(|e| {
-        error!("Failed to open repository: {:?}", e);
-        format!("Could not access repository: {}", e)
-    })?;
-
-    engine.stage_file(&file_path).map_err(|e| {
-        error!("Failed to stage file: {:?}", e);
-        format!("Could not stage file '{}': {}", file_path, e)
-    })?;
+    state
+        .run(repo_id.as_deref(), move |engine| {
+            engine.stage_file(&file_path).map_err(|e| {
+                error!("Failed to stage file: {:?}", e);
+                format!("Could not stage file '{}': {}", file_path, e)
+            })
+        })
+        .await?;
 
     info!("File staged successfully");
     Ok(())
@@ -32,24 +31,17 @@ pub async fn stage_file(file_path: String, state: State<'_, GitState>) -> Result
 /// Stage all changes
 #[tauri::command]
 #[instrument(skip(state))]
-pub async fn stage_all(state: State<'_, GitState>) -> Result<(), String> {
+pub async fn stage_all(repo_id: Option<String>, state: State<'_, GitState>) -> Result<(), String> {
     info!("Staging all changes");
 
-    let current = state.current_repo.lock().unwrap();
-    let path = current.as_ref().ok_or_else(|| {
-        warn!("No repository is currently open");
-        "No repository is currently open".to_string()
-    })?;
-
-    let engine = GitEngine::open(path).map_err(|e| {
-        error!("Failed to open repository: {:?}", e);
-        format!("Could not access repository: {}", e)
-    })?;
-
-    engine.stage_all().map_err(|e| {
-        error!("Failed to stage all: {:?}", e);
-        format!("Could not stage changes: {}", e)
-    })?;
+    state
+        .run(repo_id.as_deref(), move |engine| {
+            engine.stage_all().map_err(|e| {
+                error!("Failed to stage all: {:?}", e);
+                format!("Could not stage changes: {}", e)
+            })
+        })
+        .await?;
 
     info!("All changes staged successfully");
     Ok(())
@@ -58,24 +50,21 @@ pub async fn stage_all(state: State<'_, GitState>) -> Result<(), String> {
 /// Unstage a file
 #[tauri::command]
 #[instrument(skip(state), fields(file = %file_path))]
-pub async fn unstage_file(file_path: String, state: State<'_, GitState>) -> Result<(), String> {
+pub async fn unstage_file(
+    file_path: String,
+    repo_id: Option<String>,
+    state: State<'_, GitState>,
+) -> Result<(), String> {
     info!("Unstaging file");
 
-    let current = state.current_repo.lock().unwrap();
-    let path = current.as_ref().ok_or_else(|| {
-        warn!("No repository is currently open");
-        "No repository is currently open".to_string()
-    })?;
-
-    let engine = GitEngine::open(path).map_err(|e| {
-        error!("Failed to open repository: {:?}", e);
-        format!("Could not access repository: {}", e)
-    })?;
-
-    engine.unstage_file(&file_path).map_err(|e| {
-        error!("Failed to unstage file: {:?}", e);
-        format!("Could not unstage file '{}': {}", file_path, e)
-    })?;
+    state
+        .run(repo_id.as_deref(), move |engine| {
+            engine.unstage_file(&file_path).map_err(|e| {
+                error!("Failed to unstage file: {:?}", e);
+                format!("Could not unstage file '{}': {}", file_path, e)
+            })
+        })
+        .await?;
 
     info!("File unstaged successfully");
     Ok(())
@@ -84,29 +73,188 @@ pub async fn unstage_file(file_path: String, state: State<'_, GitState>) -> Resu
 /// Unstage all changes
 #[tauri::command]
 #[instrument(skip(state))]
-pub async fn unstage_all(state: State<'_, GitState>) -> Result<(), String> {
+pub async fn unstage_all(
+    repo_id: Option<String>,
+    state: State<'_, GitState>,
+) -> Result<(), String> {
     info!("Unstaging all changes");
 
-    let current = state.current_repo.lock().unwrap();
-    let path = current.as_ref().ok_or_else(|| {
-        warn!("No repository is currently open");
-        "No repository is currently open".to_string()
-    })?;
+    state
+        .run(repo_id.as_deref(), move |engine| {
+            engine.unstage_all().map_err(|e| {
+                error!("Failed to unstage all: {:?}", e);
+                format!("Could not unstage changes: {}", e)
+            })
+        })
+        .await?;
 
-    let engine = GitEngine::open(path).map_err(|e| {
-        error!("Failed to open repository: {:?}", e);
-        format!("Could not access repository: {}", e)
-    })?;
+    info!("All changes unstaged successfully");
+    Ok(())
+}
 
-    engine.unstage_all().map_err(|e| {
-        error!("Failed to unstage all: {:?}", e);
-        format!("Could not unstage changes: {}", e)
-    })?;
+/// Stage a hunk, or selected lines of it, from the unstaged diff
+#[tauri::command]
+#[instrument(skip(state, hunk, line_indices), fields(file = %file_path))]
+pub async fn stage_hunk(
+    file_path: String,
+    hunk: DiffHunk,
+    line_indices: Option<Vec<usize>>,
+    repo_id: Option<String>,
+    state: State<'_, GitState>,
+) -> Result<(), String> {
+    info!("Staging hunk");
+
+    state
+        .run(repo_id.as_deref(), move |engine| {
+            engine
+                .stage_hunk(&file_path, &hunk, line_indices.as_deref())
+                .map_err(|e| {
+                    error!("Failed to stage hunk: {:?}", e);
+                    format!("Could not stage hunk in '{}': {}", file_path, e)
+                })
+        })
+        .await?;
+
+    info!("Hunk staged successfully");
+    Ok(())
+}
 
-    info!("All changes unstaged successfully");
+/// Unstage a hunk, or selected lines of it, from the staged diff
+#[tauri::command]
+#[instrument(skip(state, hunk, line_indices), fields(file = %file_path))]
+pub async fn unstage_hunk(
+    file_path: String,
+    hunk: DiffHunk,
+    line_indices: Option<Vec<usize>>,
+    repo_id: Option<String>,
+    state: State<'_, GitState>,
+) -> Result<(), String> {
+    info!("Unstaging hunk");
+
+    state
+        .run(repo_id.as_deref(), move |engine| {
+            engine
+                .unstage_hunk(&file_path, &hunk, line_indices.as_deref())
+                .map_err(|e| {
+                    error!("Failed to unstage hunk: {:?}", e);
+                    format!("Could not unstage hunk in '{}': {}", file_path, e)
+                })
+        })
+        .await?;
+
+    info!("Hunk unstaged successfully");
     Ok(())
 }
 
+/// Discard a hunk, or selected lines of it, from the working tree
+#[tauri::command]
+#[instrument(skip(state, hunk, line_indices), fields(file = %file_path))]
+pub async fn discard_hunk(
+    file_path: String,
+    hunk: DiffHunk,
+    line_indices: Option<Vec<usize>>,
+    repo_id: Option<String>,
+    state: State<'_, GitState>,
+) -> Result<DiscardResult, String> {
+    info!("Discarding hunk");
+
+    let result = state
+        .run(repo_id.as_deref(), move |engine| {
+            engine
+                .discard_hunk(&file_path, &hunk, line_indices.as_deref())
+                .map_err(|e| {
+                    error!("Failed to discard hunk: {:?}", e);
+                    format!("Could not discard hunk in '{}': {}", file_path, e)
+                })
+        })
+        .await?;
+
+    info!("Hunk discarded successfully");
+    Ok(result)
+}
+
+/// Discard changes to files, restoring them from the index or from HEAD
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn discard_changes(
+    file_paths: Vec<String>,
+    source: Option<RestoreSource>,
+    repo_id: Option<String>,
+    state: State<'_, GitState>,
+) -> Result<DiscardResult, String> {
+    info!("Discarding changes");
+
+    let result = state
+        .run(repo_id.as_deref(), move |engine| {
+            engine
+                .discard_changes(&file_paths, source.unwrap_or(RestoreSource::Index))
+                .map_err(|e| {
+                    error!("Failed to discard changes: {:?}", e);
+                    format!("Could not discard changes: {}", e)
+                })
+        })
+        .await?;
+
+    info!(count = result.paths.len(), "Changes discarded successfully");
+    Ok(result)
+}
+
+/// Delete untracked files, or list them when `dry_run` is set
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn clean_untracked(
+    file_paths: Vec<String>,
+    dry_run: Option<bool>,
+    repo_id: Option<String>,
+    state: State<'_, GitState>,
+) -> Result<DiscardResult, String> {
+    info!("Cleaning untracked files");
+
+    let result = state
+        .run(repo_id.as_deref(), move |engine| {
+            engine
+                .clean_untracked(&file_paths, dry_run.unwrap_or(false))
+                .map_err(|e| {
+                    error!("Failed to clean untracked files: {:?}", e);
+                    format!("Could not clean untracked files: {}", e)
+                })
+        })
+        .await?;
+
+    info!(
+        count = result.paths.len(),
+        dry_run = result.dry_run,
+        "Clean finished"
+    );
+    Ok(result)
+}
+
+/// Restore files saved before a discard or clean
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn restore_discard_backup(
+    backup_ref: String,
+    repo_id: Option<String>,
+    state: State<'_, GitState>,
+) -> Result<Vec<String>, String> {
+    info!("Restoring discarded files");
+
+    let restored = state
+        .run(repo_id.as_deref(), move |engine| {
+            engine.restore_discard_backup(&backup_ref).map_err(|e| {
+                error!("Failed to restore discarded files: {:?}", e);
+                format!("Could not restore '{}': {}", backup_ref, e)
+            })
+        })
+        .await?;
+
+    info!(
+        count = restored.len(),
+        "Discarded files restored successfully"
+    );
+    Ok(restored)
+}
+
 /// Create a commit
 #[tauri::command]
 #[instrument(skip(state, message, author_name, author_email), fields(message_len = message.len()))]
@@ -114,56 +262,68 @@ pub async fn create_commit(
     message: String,
     author_name: String,
     author_email: String,
+    repo_id: Option<String>,
     state: State<'_, GitState>,
 ) -> Result<String, String> {
     info!("Creating commit");
 
-    let current = state.current_repo.lock().unwrap();
-    let path = current.as_ref().ok_or_else(|| {
-        warn!("No repository is currently open");
-        "No repository is currently open".to_string()
-    })?;
-
-    let engine = GitEngine::open(path).map_err(|e| {
-        error!("Failed to open repository: {:?}", e);
-        format!("Could not access repository: {}", e)
-    })?;
-
-    let commit_hash = engine
-        .create_commit(&message, &author_name, &author_email)
-        .map_err(|e| {
-            error!("Failed to create commit: {:?}", e);
-            format!("Could not create commit: {}", e)
-        })?;
+    let commit_hash = state
+        .run(repo_id.as_deref(), move |engine| {
+            engine
+                .create_commit(&message, &author_name, &author_email)
+                .map_err(|e| {
+                    error!("Failed to create commit: {:?}", e);
+                    format!("Could not create commit: {}", e)
+                })
+        })
+        .await?;
 
     info!(commit_hash = %commit_hash, "Commit created successfully");
     Ok(commit_hash)
 }
 
+/// Reset the current branch to a revision
+#[tauri::command]
+#[instrument(skip(state), fields(target = %target))]
+pub async fn reset_to(
+    target: String,
+    mode: ResetMode,
+    repo_id: Option<String>,
+    state: State<'_, GitState>,
+) -> Result<ResetResult, String> {
+    info!(?mode, "Resetting to revision");
+
+    let result = state
+        .run(repo_id.as_deref(), move |engine| {
+            engine.reset_to(&target, mode).map_err(|e| {
+                error!("Failed to reset: {:?}", e);
+                format!("Could not reset to '{}': {}", target, e)
+            })
+        })
+        .await?;
+
+    info!(backup_ref = ?result.backup_ref, "Reset completed successfully");
+    Ok(result)
+}
+
 /// Checkout a branch
 #[tauri::command]
 #[instrument(skip(state), fields(branch = %branch_name))]
 pub async fn checkout_branch(
     branch_name: String,
+    repo_id: Option<String>,
     state: State<'_, GitState>,
 ) -> Result<(), String> {
     info!("Checking out branch");
 
-    let current = state.current_repo.lock().unwrap();
-    let path = current.as_ref().ok_or_else(|| {
-        warn!("No repository is currently open");
-        "No repository is currently open".to_string()
-    })?;
-
-    let engine = GitEngine::open(path).map_err(|e| {
-        error!("Failed to open repository: {:?}", e);
-        format!("Could not access repository: {}", e)
-    })?;
-
-    engine.checkout_branch(&branch_name).map_err(|e| {
-        error!("Failed to checkout branch: {:?}", e);
-        format!("Could not checkout branch '{}': {}", branch_name, e)
-    })?;
+    state
+        .run(repo_id.as_deref(), move |engine| {
+            engine.checkout_branch(&branch_name).map_err(|e| {
+                error!("Failed to checkout branch: {:?}", e);
+                format!("Could not checkout branch '{}': {}", branch_name, e)
+            })
+        })
+        .await?;
 
     info!("Branch checked out successfully");
     Ok(())
@@ -175,25 +335,19 @@ pub async fn checkout_branch(
 pub async fn create_branch(
     name: String,
     from: Option<String>,
+    repo_id: Option<String>,
     state: State<'_, GitState>,
 ) -> Result<(), String> {
     info!("Creating new branch");
 
-    let current = state.current_repo.lock().unwrap();
-    let path = current.as_ref().ok_or_else(|| {
-        warn!("No repository is currently open");
-        "No repository is currently open".to_string()
-    })?;
-
-    let engine = GitEngine::open(path).map_err(|e| {
-        error!("Failed to open repository: {:?}", e);
-        format!("Could not access repository: {}", e)
-    })?;
-
-    engine.create_branch(&name, from.as_deref()).map_err(|e| {
-        error!("Failed to create branch: {:?}", e);
-        format!("Could not create branch '{}': {}", name, e)
-    })?;
+    state
+        .run(repo_id.as_deref(), move |engine| {
+            engine.create_branch(&name, from.as_deref()).map_err(|e| {
+                error!("Failed to create branch: {:?}", e);
+                format!("Could not create branch '{}': {}", name, e)
+            })
+        })
+        .await?;
 
     info!("Branch created successfully");
     Ok(())
@@ -205,25 +359,19 @@ pub async fn create_branch(
 pub async fn delete_branch(
     name: String,
     force: bool,
+    repo_id: Option<String>,
     state: State<'_, GitState>,
 ) -> Result<(), String> {
     info!("Deleting branch");
 
-    let current = state.current_repo.lock().unwrap();
-    let path = current.as_ref().ok_or_else(|| {
-        warn!("No repository is currently open");
-        "No repository is currently open".to_string()
-    })?;
-
-    let engine = GitEngine::open(path).map_err(|e| {
-        error!("Failed to open repository: {:?}", e);
-        format!("Could not access repository: {}", e)
-    })?;
-
-    engine.delete_branch(&name, force).map_err(|e| {
-        error!("Failed to delete branch: {:?}", e);
-        format!("Could not delete branch '{}': {}", name, e)
-    })?;
+    state
+        .run(repo_id.as_deref(), move |engine| {
+            engine.delete_branch(&name, force).map_err(|e| {
+                error!("Failed to delete branch: {:?}", e);
+                format!("Could not delete branch '{}': {}", name, e)
+            })
+        })
+        .await?;
 
     info!("Branch deleted successfully");
     Ok(())
@@ -1,31 +1,26 @@
 use crate::commands::git_state::GitState;
 use crate::core::git_stash_operations::GitStashOperations;
 use crate::models::git_repository::Stash;
-use git2::Repository;
 use tauri::State;
-use tracing::{error, info, instrument, warn};
+use tracing::{error, info, instrument};
 
 /// List all stashes in the current repository
 #[tauri::command]
 #[instrument(skip(state))]
-pub async fn list_stashes(state: State<'_, GitState>) -> Result<Vec<Stash>, String> {
+pub async fn list_stashes(
+    repo_id: Option<String>,
+    state: State<'_, GitState>,
+) -> Result<Vec<Stash>, String> {
     info!("Listing stashes");
 
-    let current = state.current_repo.lock().unwrap();
-    let path = current.as_ref().ok_or_else(|| {
-        warn!("No repository is currently open");
-        "No repository is currently open".to_string()
-    })?;
-
-    let mut repo = Repository::open(path).map_err(|e| {
-        error!("Failed to open repository: {:?}", e);
-        format!("Could not access repository: {}", e)
-    })?;
-
-    let stashes = repo.list_stashes().map_err(|e| {
-        error!("Failed to list stashes: {:?}", e);
-        format!("Could not list stashes: {}", e)
-    })?;
+    let stashes = state
+        .run(repo_id.as_deref(), move |engine| {
+            engine.repo.list_stashes().map_err(|e| {
+                error!("Failed to list stashes: {:?}", e);
+                format!("Could not list stashes: {}", e)
+            })
+        })
+        .await?;
 
     info!(count = stashes.len(), "Stashes listed successfully");
     Ok(stashes)
@@ -36,25 +31,19 @@ pub async fn list_stashes(state: State<'_, GitState>) -> Result<Vec<Stash>, Stri
 #[instrument(skip(state), fields(message = ?message))]
 pub async fn create_stash(
     message: Option<String>,
+    repo_id: Option<String>,
     state: State<'_, GitState>,
 ) -> Result<String, String> {
     info!("Creating stash");
 
-    let current = state.current_repo.lock().unwrap();
-    let path = current.as_ref().ok_or_else(|| {
-        warn!("No repository is currently open");
-        "No repository is currently open".to_string()
-    })?;
-
-    let mut repo = Repository::open(path).map_err(|e| {
-        error!("Failed to open repository: {:?}", e);
-        format!("Could not access repository: {}", e)
-    })?;
-
-    let stash_id = repo.create_stash(message.as_deref()).map_err(|e| {
-        error!("Failed to create stash: {:?}", e);
-        format!("Could not create stash: {}", e)
-    })?;
+    let stash_id = state
+        .run(repo_id.as_deref(), move |engine| {
+            engine.repo.create_stash(message.as_deref()).map_err(|e| {
+                error!("Failed to create stash: {:?}", e);
+                format!("Could not create stash: {}", e)
+            })
+        })
+        .await?;
 
     info!(stash_id = %stash_id, "Stash created successfully");
     Ok(stash_id)
@@ -63,24 +52,21 @@ pub async fn create_stash(
 /// Apply a stash by index (keeps the stash)
 #[tauri::command]
 #[instrument(skip(state), fields(index = index))]
-pub async fn apply_stash(index: usize, state: State<'_, GitState>) -> Result<(), String> {
+pub async fn apply_stash(
+    index: usize,
+    repo_id: Option<String>,
+    state: State<'_, GitState>,
+) -> Result<(), String> {
     info!("Applying stash");
 
-    let current = state.current_repo.lock().unwrap();
-    let path = current.as_ref().ok_or_else(|| {
-        warn!("No repository is currently open");
-        "No repository is currently open".to_string()
-    })?;
-
-    let mut repo = Repository::open(path).map_err(|e| {
-        error!("Failed to open repository: {:?}", e);
-        format!("Could not access repository: {}", e)
-    })?;
-
-    repo.apply_stash(index).map_err(|e| {
-        error!("Failed to apply stash: {:?}", e);
-        format!("Could not apply stash at index {}: {}", index, e)
-    })?;
+    state
+        .run(repo_id.as_deref(), move |engine| {
+            engine.repo.apply_stash(index).map_err(|e| {
+                error!("Failed to apply stash: {:?}", e);
+                format!("Could not apply stash at index {}: {}", index, e)
+            })
+        })
+        .await?;
 
     info!("Stash applied successfully");
     Ok(())
@@ -89,24 +75,21 @@ pub async fn apply_stash(index: usize, state: State<'_, GitState>) -> Result<(),
 /// Pop a stash by index (applies and removes it)
 #[tauri::command]
 #[instrument(skip(state), fields(index = index))]
-pub async fn pop_stash(index: usize, state: State<'_, GitState>) -> Result<(), String> {
+pub async fn pop_stash(
+    index: usize,
+    repo_id: Option<String>,
+    state: State<'_, GitState>,
+) -> Result<(), String> {
     info!("Popping stash");
 
-    let current = state.current_repo.lock().unwrap();
-    let path = current.as_ref().ok_or_else(|| {
-        warn!("No repository is currently open");
-        "No repository is currently open".to_string()
-    })?;
-
-    let mut repo = Repository::open(path).map_err(|e| {
-        error!("Failed to open repository: {:?}", e);
-        format!("Could not access repository: {}", e)
-    })?;
-
-    repo.pop_stash(index).map_err(|e| {
-        error!("Failed to pop stash: {:?}", e);
-        format!("Could not pop stash at index {}: {}", index, e)
-    })?;
+    state
+        .run(repo_id.as_deref(), move |engine| {
+            engine.repo.pop_stash(index).map_err(|e| {
+                error!("Failed to pop stash: {:?}", e);
+                format!("Could not pop stash at index {}: {}", index, e)
+            })
+        })
+        .await?;
 
     info!("Stash popped successfully");
     Ok(())
@@ -115,24 +98,21 @@ pub async fn pop_stash(index: usize, state: State<'_, GitState>) -> Result<(), S
 /// Drop (delete) a stash by index
 #[tauri::command]
 #[instrument(skip(state), fields(index = index))]
-pub async fn drop_stash(index: usize, state: State<'_, GitState>) -> Result<(), String> {
+pub async fn drop_stash(
+    index: usize,
+    repo_id: Option<String>,
+    state: State<'_, GitState>,
+) -> Result<(), String> {
     info!("Dropping stash");
 
-    let current = state.current_repo.lock().unwrap();
-    let path = current.as_ref().ok_or_else(|| {
-        warn!("No repository is currently open");
-        "No repository is currently open".to_string()
-    })?;
-
-    let mut repo = Repository::open(path).map_err(|e| {
-        error!("Failed to open repository: {:?}", e);
-        format!("Could not access repository: {}", e)
-    })?;
-
-    repo.drop_stash(index).map_err(|e| {
-        error!("Failed to drop stash: {:?}", e);
-        format!("Could not drop stash at index {}: {}", index, e)
-    })?;
+    state
+        .run(repo_id.as_deref(), move |engine| {
+            engine.repo.drop_stash(index).map_err(|e| {
+                error!("Failed to drop stash: {:?}", e);
+                format!("Could not drop stash at index {}: {}", index, e)
+            })
+        })
+        .await?;
 
     info!("Stash dropped successfully");
     Ok(())
@@ -1,15 +1,234 @@
-use std::path::PathBuf;
-use std::sync::Mutex;
+use crate::core::git_engine::GitEngine;
+use crate::core::git_progress::OperationProgress;
+use crate::core::git_session::GitSession;
+use crate::core::git_watcher::RepositoryWatcher;
+use crate::models::git_repository::{
+    OpenRepositoryInfo, RepositoryChangeEvent, RepositoryChangeKind,
+};
+use chrono::Utc;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Emitter};
+use tracing::{error, warn, Span};
+
+/// Event carrying `TransferProgress` for fetch, push and pull
+pub const PROGRESS_EVENT: &str = "git:progress";
+
+/// Events carrying a `RepositoryChangeEvent` from the repository watcher
+pub const STATUS_CHANGED_EVENT: &str = "repo:status-changed";
+pub const HEAD_CHANGED_EVENT: &str = "repo:head-changed";
+pub const REFS_CHANGED_EVENT: &str = "repo:refs-changed";
+
+/// A repository open in the app; its session and watcher end when it is closed
+pub(crate) struct OpenRepository {
+    pub(crate) path: PathBuf,
+    /// Resolved path, so a symlink or `..` spelling finds the same entry
+    canonical_path: PathBuf,
+    session: GitSession,
+    _watcher: Option<RepositoryWatcher>,
+}
 
 /// Global repository state
 pub struct GitState {
-    pub(crate) current_repo: Mutex<Option<PathBuf>>,
+    /// Open repositories keyed by repository id
+    pub(crate) repositories: Mutex<HashMap<String, OpenRepository>>,
+    /// Repository used by commands that are not given a repository id
+    pub(crate) active_repo: Mutex<Option<String>>,
+    next_repo_id: AtomicU64,
+    /// Cancellation flags of in-flight network operations, keyed by operation id
+    pub(crate) operations: Mutex<HashMap<String, Arc<AtomicBool>>>,
 }
 
 impl GitState {
     pub fn new() -> Self {
         Self {
-            current_repo: Mutex::new(None),
+            repositories: Mutex::new(HashMap::new()),
+            active_repo: Mutex::new(None),
+            next_repo_id: AtomicU64::new(1),
+            operations: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Add an opened repository to the registry, start watching it and make it
+    /// the active one. A path that is already open keeps its id and session.
+    ///
+    /// Starting the watcher walks the working tree, so call this from a
+    /// blocking worker thread.
+    pub(crate) fn register_repository(&self, app: &AppHandle, engine: GitEngine) -> String {
+        let path = engine.repo_path.clone();
+        let canonical_path = path.canonicalize().unwrap_or_else(|_| path.clone());
+        let id = match self.find_repository(&canonical_path) {
+            Some(id) => id,
+            None => {
+                // Watch before taking the lock, then check nobody opened it meanwhile
+                let id = format!("repo-{}", self.next_repo_id.fetch_add(1, Ordering::Relaxed));
+                let watcher = watch_repository(app, &id, &path);
+
+                let mut repositories = self.repositories.lock().unwrap();
+                let existing = repositories
+                    .iter()
+                    .find(|(_, repo)| repo.canonical_path == canonical_path);
+                match existing {
+                    Some((existing, _)) => existing.clone(),
+                    None => {
+                        repositories.insert(
+                            id.clone(),
+                            OpenRepository {
+                                path,
+                                canonical_path,
+                                session: GitSession::new(engine),
+                                _watcher: watcher,
+                            },
+                        );
+                        id
+                    }
+                }
+            }
+        };
+
+        *self.active_repo.lock().unwrap() = Some(id.clone());
+        id
+    }
+
+    /// Remove a repository from the registry; returns false if it was not open
+    pub(crate) fn close_repository(&self, repo_id: &str) -> bool {
+        let removed = self.repositories.lock().unwrap().remove(repo_id).is_some();
+        let mut active = self.active_repo.lock().unwrap();
+        if active.as_deref() == Some(repo_id) {
+            *active = None;
+        }
+        removed
+    }
+
+    /// Make an open repository the one used when no id is given; returns false
+    /// if it is not open
+    pub(crate) fn set_active_repository(&self, repo_id: &str) -> bool {
+        if !self.repositories.lock().unwrap().contains_key(repo_id) {
+            return false;
+        }
+        *self.active_repo.lock().unwrap() = Some(repo_id.to_string());
+        true
+    }
+
+    /// Path of the given repository, or of the active one when no id is given
+    pub(crate) fn repository_path(&self, repo_id: Option<&str>) -> Result<PathBuf, String> {
+        self.with_repository(repo_id, |repo| repo.path.clone())
+    }
+
+    /// Session of the given repository, or of the active one when no id is given
+    pub(crate) fn session(&self, repo_id: Option<&str>) -> Result<GitSession, String> {
+        self.with_repository(repo_id, |repo| repo.session.clone())
+    }
+
+    /// Run `operation` with the repository's cached engine on a blocking worker
+    /// thread. The registry lock is released before the operation starts;
+    /// operations on the same repository wait for each other.
+    pub(crate) async fn run<T, F>(&self, repo_id: Option<&str>, operation: F) -> Result<T, String>
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut GitEngine) -> Result<T, String> + Send + 'static,
+    {
+        let session = self.session(repo_id)?;
+        run_blocking(move || {
+            let mut engine = session.lock().map_err(|e| {
+                error!("Failed to lock repository session: {:?}", e);
+                format!("Could not access repository: {}", e)
+            })?;
+            operation(&mut engine)
+        })
+        .await
+    }
+
+    /// Like `run`, but with an engine of its own so a long network transfer
+    /// does not hold up the repository's other commands
+    pub(crate) async fn run_standalone<T, F>(
+        &self,
+        repo_id: Option<&str>,
+        operation: F,
+    ) -> Result<T, String>
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut GitEngine) -> Result<T, String> + Send + 'static,
+    {
+        let path = self.repository_path(repo_id)?;
+        run_blocking(move || {
+            let mut engine = GitEngine::open(&path).map_err(|e| {
+                error!("Failed to open repository: {:?}", e);
+                format!("Could not access repository: {}", e)
+            })?;
+            operation(&mut engine)
+        })
+        .await
+    }
+
+    pub(crate) fn list_repositories(&self) -> Vec<OpenRepositoryInfo> {
+        let active = self.active_repo.lock().unwrap().clone();
+        let mut repositories: Vec<OpenRepositoryInfo> = self
+            .repositories
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(id, repo)| OpenRepositoryInfo {
+                id: id.clone(),
+                path: repo.path.to_string_lossy().to_string(),
+                name: repo
+                    .path
+                    .file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_default(),
+                is_active: active.as_deref() == Some(id.as_str()),
+            })
+            .collect();
+        repositories.sort_by(|a, b| a.name.cmp(&b.name).then_with(|| a.id.cmp(&b.id)));
+        repositories
+    }
+
+    /// Register a cancellable operation whose progress is emitted as `git:progress`.
+    /// An id that is still in flight is rejected, as cancelling it would be ambiguous.
+    pub(crate) fn begin_operation(
+        &self,
+        app: &AppHandle,
+        operation_id: Option<String>,
+        kind: &str,
+    ) -> Result<OperationProgress, String> {
+        let id = operation_id.unwrap_or_else(|| {
+            format!("{}-{}", kind, Utc::now().timestamp_nanos_opt().unwrap_or(0))
+        });
+
+        let mut operations = self.operations.lock().unwrap();
+        if operations.contains_key(&id) {
+            warn!(operation_id = %id, "Operation id is already in use");
+            return Err(format!("Operation '{}' is already running", id));
+        }
+
+        let app = app.clone();
+        let progress = OperationProgress::new(
+            id.clone(),
+            Arc::new(move |event| {
+                if let Err(e) = app.emit(PROGRESS_EVENT, event) {
+                    error!("Failed to emit progress event: {}", e);
+                }
+            }),
+        );
+
+        operations.insert(id, progress.cancel_flag());
+        Ok(progress)
+    }
+
+    pub(crate) fn finish_operation(&self, operation_id: &str) {
+        self.operations.lock().unwrap().remove(operation_id);
+    }
+
+    /// Flag an in-flight operation as cancelled; returns false if it is unknown
+    pub(crate) fn cancel_operation(&self, operation_id: &str) -> bool {
+        match self.operations.lock().unwrap().get(operation_id) {
+            Some(flag) => {
+                flag.store(true, Ordering::Relaxed);
+                true
+            }
+            None => false,
         }
     }
 }
@@ -19,3 +238,80 @@ impl Default for GitState {
         Self::new()
     }
 }
+
+// Helper functions
+
+impl GitState {
+    fn find_repository(&self, canonical_path: &Path) -> Option<String> {
+        self.repositories
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|(_, repo)| repo.canonical_path == canonical_path)
+            .map(|(id, _)| id.clone())
+    }
+
+    /// Look up a repository by id, or the active one, holding the lock only for `read`
+    fn with_repository<T>(
+        &self,
+        repo_id: Option<&str>,
+        read: impl FnOnce(&OpenRepository) -> T,
+    ) -> Result<T, String> {
+        let id = match repo_id {
+            Some(id) => id.to_string(),
+            None => self.active_repo.lock().unwrap().clone().ok_or_else(|| {
+                warn!("No repository is currently open");
+                "No repository is currently open".to_string()
+            })?,
+        };
+
+        self.repositories
+            .lock()
+            .unwrap()
+            .get(&id)
+            .map(read)
+            .ok_or_else(|| {
+                warn!(repo_id = %id, "Repository is not open");
+                format!("Repository '{}' is not open", id)
+            })
+    }
+}
+
+/// Run blocking git work on a worker thread, keeping the caller's tracing span
+pub(crate) async fn run_blocking<T, F>(operation: F) -> Result<T, String>
+where
+    T: Send + 'static,
+    F: FnOnce() -> Result<T, String> + Send + 'static,
+{
+    let span = Span::current();
+    tauri::async_runtime::spawn_blocking(move || span.in_scope(operation))
+        .await
+        .map_err(|e| {
+            error!("Git worker thread failed: {}", e);
+            format!("Git operation failed unexpectedly: {}", e)
+        })?
+}
+
+/// Watch a repository and emit its `repo:*-changed` events. Failing to watch is
+/// logged rather than returned, as the repository is usable without live updates.
+fn watch_repository(app: &AppHandle, repo_id: &str, path: &Path) -> Option<RepositoryWatcher> {
+    let app = app.clone();
+    let sink = Arc::new(move |event: RepositoryChangeEvent| {
+        let name = match event.kind {
+            RepositoryChangeKind::Status => STATUS_CHANGED_EVENT,
+            RepositoryChangeKind::Head => HEAD_CHANGED_EVENT,
+            RepositoryChangeKind::Refs => REFS_CHANGED_EVENT,
+        };
+        if let Err(e) = app.emit(name, event) {
+            error!("Failed to emit repository event: {}", e);
+        }
+    });
+
+    match RepositoryWatcher::start(path, repo_id, sink) {
+        Ok(watcher) => Some(watcher),
+        Err(e) => {
+            warn!("Could not watch repository: {}", e);
+            None
+        }
+    }
+}
@@ -0,0 +1,106 @@
+use crate::commands::git_state::GitState;
+use crate::models::git_repository::Tag;
+use tauri::State;
+use tracing::{error, info, instrument};
+
+/// List all tags
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn list_tags(
+    repo_id: Option<String>,
+    state: State<'_, GitState>,
+) -> Result<Vec<Tag>, String> {
+    info!("Listing tags");
+
+    let tags = state
+        .run(repo_id.as_deref(), move |engine| {
+            engine.list_tags().map_err(|e| {
+                error!("Failed to list tags: {:?}", e);
+                format!("Could not retrieve tags: {}", e)
+            })
+        })
+        .await?;
+
+    info!(count = tags.len(), "Tags listed successfully");
+    Ok(tags)
+}
+
+/// Create a tag; an annotated tag is created when a message is provided
+#[tauri::command]
+#[instrument(skip(state, message))]
+pub async fn create_tag(
+    name: String,
+    target: Option<String>,
+    message: Option<String>,
+    repo_id: Option<String>,
+    state: State<'_, GitState>,
+) -> Result<String, String> {
+    info!(name, ?target, annotated = message.is_some(), "Creating tag");
+
+    let oid = state
+        .run(repo_id.as_deref(), move |engine| {
+            let result = match message.as_deref() {
+                Some(message) => engine.create_annotated_tag(&name, target.as_deref(), message),
+                None => engine.create_lightweight_tag(&name, target.as_deref()),
+            };
+
+            result.map_err(|e| {
+                error!("Failed to create tag: {:?}", e);
+                format!("Could not create tag '{}': {}", name, e)
+            })
+        })
+        .await?;
+
+    info!(oid = %oid, "Tag created successfully");
+    Ok(oid)
+}
+
+/// Delete a local tag
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn delete_tag(
+    name: String,
+    repo_id: Option<String>,
+    state: State<'_, GitState>,
+) -> Result<(), String> {
+    info!(name, "Deleting tag");
+
+    state
+        .run(repo_id.as_deref(), move |engine| {
+            engine.delete_tag(&name).map_err(|e| {
+                error!("Failed to delete tag: {:?}", e);
+                format!("Could not delete tag '{}': {}", name, e)
+            })
+        })
+        .await?;
+
+    info!("Tag deleted successfully");
+    Ok(())
+}
+
+/// Push a tag to a remote
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn push_tag(
+    remote: String,
+    name: String,
+    force: Option<bool>,
+    repo_id: Option<String>,
+    state: State<'_, GitState>,
+) -> Result<(), String> {
+    info!(remote, name, force, "Pushing tag");
+
+    state
+        .run(repo_id.as_deref(), move |engine| {
+            engine
+                .push_tag(&remote, &name, force.unwrap_or(false))
+                .map_err(|e| {
+                    error!("Failed to push tag: {:?}", e);
+                    format!("Could not push tag '{}': {}", name, e)
+                })
+        })
+        .await?;
+
+    info!("Tag pushed successfully");
+    Ok(())
+}
@@ -0,0 +1,145 @@
+use crate::commands::git_repository_commands::open_and_register;
+use crate::commands::git_state::{run_blocking, GitState};
+use crate::models::git_repository::{AddWorktreeOptions, RepositoryInfo, Worktree};
+use tauri::{AppHandle, State};
+use tracing::{error, info, instrument};
+
+/// List the main working tree and all linked worktrees
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn list_worktrees(
+    repo_id: Option<String>,
+    state: State<'_, GitState>,
+) -> Result<Vec<Worktree>, String> {
+    info!("Listing worktrees");
+
+    let worktrees = state
+        .run(repo_id.as_deref(), move |engine| {
+            engine.list_worktrees().map_err(|e| {
+                error!("Failed to list worktrees: {:?}", e);
+                format!("Could not list worktrees: {}", e)
+            })
+        })
+        .await?;
+
+    info!(count = worktrees.len(), "Worktrees listed successfully");
+    Ok(worktrees)
+}
+
+/// Add a worktree for a new or existing branch
+#[tauri::command]
+#[instrument(skip(state, options), fields(path = %options.path, branch = %options.branch))]
+pub async fn add_worktree(
+    options: AddWorktreeOptions,
+    repo_id: Option<String>,
+    state: State<'_, GitState>,
+) -> Result<Worktree, String> {
+    info!(create_branch = options.create_branch, "Adding worktree");
+
+    let worktree = state
+        .run(repo_id.as_deref(), move |engine| {
+            engine.add_worktree(&options).map_err(|e| {
+                error!("Failed to add worktree: {:?}", e);
+                format!("Could not add worktree at '{}': {}", options.path, e)
+            })
+        })
+        .await?;
+
+    info!(name = ?worktree.name, "Worktree added successfully");
+    Ok(worktree)
+}
+
+/// Lock a worktree so it is not pruned
+#[tauri::command]
+#[instrument(skip(state, reason))]
+pub async fn lock_worktree(
+    name: String,
+    reason: Option<String>,
+    repo_id: Option<String>,
+    state: State<'_, GitState>,
+) -> Result<(), String> {
+    info!(name, "Locking worktree");
+
+    state
+        .run(repo_id.as_deref(), move |engine| {
+            engine.lock_worktree(&name, reason.as_deref()).map_err(|e| {
+                error!("Failed to lock worktree: {:?}", e);
+                format!("Could not lock worktree '{}': {}", name, e)
+            })
+        })
+        .await?;
+
+    info!("Worktree locked successfully");
+    Ok(())
+}
+
+/// Unlock a worktree
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn unlock_worktree(
+    name: String,
+    repo_id: Option<String>,
+    state: State<'_, GitState>,
+) -> Result<(), String> {
+    info!(name, "Unlocking worktree");
+
+    state
+        .run(repo_id.as_deref(), move |engine| {
+            engine.unlock_worktree(&name).map_err(|e| {
+                error!("Failed to unlock worktree: {:?}", e);
+                format!("Could not unlock worktree '{}': {}", name, e)
+            })
+        })
+        .await?;
+
+    info!("Worktree unlocked successfully");
+    Ok(())
+}
+
+/// Prune worktrees whose directories have been deleted, returning their names
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn prune_worktrees(
+    repo_id: Option<String>,
+    state: State<'_, GitState>,
+) -> Result<Vec<String>, String> {
+    info!("Pruning worktrees");
+
+    let pruned = state
+        .run(repo_id.as_deref(), move |engine| {
+            engine.prune_worktrees().map_err(|e| {
+                error!("Failed to prune worktrees: {:?}", e);
+                format!("Could not prune worktrees: {}", e)
+            })
+        })
+        .await?;
+
+    info!(count = pruned.len(), "Worktrees pruned successfully");
+    Ok(pruned)
+}
+
+/// Open a linked worktree and make it the current repository
+#[tauri::command]
+#[instrument(skip(app, state))]
+pub async fn open_worktree(
+    name: String,
+    app: AppHandle,
+    repo_id: Option<String>,
+    state: State<'_, GitState>,
+) -> Result<RepositoryInfo, String> {
+    info!(name, "Opening worktree");
+
+    let path = state
+        .run(repo_id.as_deref(), move |engine| {
+            engine.worktree_path(&name).map_err(|e| {
+                error!("Failed to find worktree: {:?}", e);
+                format!("Could not open worktree '{}': {}", name, e)
+            })
+        })
+        .await?;
+
+    let info = run_blocking(move || open_and_register(&app, &path)).await?;
+
+    info!("Worktree opened successfully");
+    Ok(info)
+}
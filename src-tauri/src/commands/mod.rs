@@ -4,13 +4,19 @@ pub mod progress_commands;
 pub mod project_commands;
 
 // Git command modules
+pub mod git_credential_commands;
 pub mod git_diff_commands;
 pub mod git_history_commands;
+pub mod git_merge_commands;
+pub mod git_rebase_commands;
+pub mod git_reflog_commands;
 pub mod git_remote_commands;
 pub mod git_repository_commands;
 pub mod git_staging_commands;
 pub mod git_stash_commands;
 pub mod git_state;
+pub mod git_tag_commands;
+pub mod git_worktree_commands;
 
 // Re-export all commands for easy access
 pub use checklist_commands::*;
@@ -19,10 +25,16 @@ pub use progress_commands::*;
 pub use project_commands::*;
 
 // Re-export git commands
+pub use git_credential_commands::*;
 pub use git_diff_commands::*;
 pub use git_history_commands::*;
+pub use git_merge_commands::*;
+pub use git_rebase_commands::*;
+pub use git_reflog_commands::*;
 pub use git_remote_commands::*;
 pub use git_repository_commands::*;
 pub use git_staging_commands::*;
 pub use git_stash_commands::*;
 pub use git_state::GitState;
+pub use git_tag_commands::*;
+pub use git_worktree_commands::*;
@@ -0,0 +1,121 @@
+use crate::core::git_credentials::{
+    credential_callbacks, credential_callbacks_with_config, map_remote_error,
+};
+use crate::core::git_engine::GitEngine;
+use crate::core::git_error::{GitError, GitResult};
+use crate::core::git_progress::OperationProgress;
+use crate::models::git_repository::CloneOptions;
+use git2::build::{CheckoutBuilder, RepoBuilder};
+use git2::{Config, FetchOptions, Repository as Git2Repository, RepositoryInitOptions};
+use std::path::Path;
+use tracing::{debug, info, instrument};
+
+/// Creating repositories, as opposed to opening existing ones
+impl GitEngine {
+    /// Clone a remote repository, optionally shallow and with its submodules
+    #[instrument(skip(options, progress), fields(url = %options.url, destination = %options.destination))]
+    pub fn clone_repository(
+        options: &CloneOptions,
+        progress: Option<&OperationProgress>,
+    ) -> GitResult<Self> {
+        info!("Cloning repository");
+        let destination = Path::new(&options.destination);
+
+        let mut callbacks = credential_callbacks_with_config(Config::open_default().ok());
+        if let Some(progress) = progress {
+            progress.attach(&mut callbacks);
+        }
+
+        let mut fetch_options = FetchOptions::new();
+        fetch_options.remote_callbacks(callbacks);
+        if let Some(depth) = options.depth {
+            let depth = i32::try_from(depth).map_err(|_| {
+                GitError::OperationFailed(format!("Clone depth {} is too large", depth))
+            })?;
+            fetch_options.depth(depth);
+        }
+
+        let mut checkout = CheckoutBuilder::new();
+        if let Some(progress) = progress {
+            progress.attach_checkout(&mut checkout);
+        }
+
+        let mut builder = RepoBuilder::new();
+        builder.fetch_options(fetch_options).with_checkout(checkout);
+        if let Some(branch) = &options.branch {
+            builder.branch(branch);
+        }
+
+        let repo = builder
+            .clone(&options.url, destination)
+            .map_err(|e| map_remote_error(e, &options.url, progress))?;
+
+        if options.recursive_submodules {
+            update_submodules(&repo, progress)?;
+        }
+
+        info!("Repository cloned successfully");
+        Self::open(destination)
+    }
+
+    /// Create a new repository, optionally with an empty first commit
+    #[instrument(skip(path, first_commit_message), fields(path = %path.as_ref().display()))]
+    pub fn init_repository<P: AsRef<Path>>(
+        path: P,
+        initial_branch: Option<&str>,
+        first_commit_message: Option<&str>,
+    ) -> GitResult<Self> {
+        info!("Initializing repository");
+        let path = path.as_ref();
+
+        let mut init_options = RepositoryInitOptions::new();
+        init_options.mkpath(true);
+        if let Some(branch) = initial_branch {
+            init_options.initial_head(branch);
+        }
+        let repo = Git2Repository::init_opts(path, &init_options)?;
+
+        if let Some(message) = first_commit_message {
+            let signature = repo.signature().map_err(|e| {
+                GitError::OperationFailed(format!(
+                    "Set user.name and user.email before creating the first commit ({})",
+                    e.message()
+                ))
+            })?;
+            let tree = repo.find_tree(repo.index()?.write_tree()?)?;
+            let oid = repo.commit(Some("HEAD"), &signature, &signature, message, &tree, &[])?;
+            debug!(commit = %oid, "First commit created");
+        }
+
+        info!("Repository initialized successfully");
+        Self::open(path)
+    }
+}
+
+// Helper functions
+
+fn update_submodules(repo: &Git2Repository, progress: Option<&OperationProgress>) -> GitResult<()> {
+    for mut submodule in repo.submodules()? {
+        let name = submodule.name().unwrap_or("").to_string();
+        debug!(submodule = %name, "Updating submodule");
+
+        let mut callbacks = credential_callbacks(repo);
+        if let Some(progress) = progress {
+            progress.attach(&mut callbacks);
+        }
+        let mut fetch_options = FetchOptions::new();
+        fetch_options.remote_callbacks(callbacks);
+
+        let mut update_options = git2::SubmoduleUpdateOptions::new();
+        update_options.fetch(fetch_options);
+
+        let url = submodule.url().unwrap_or(&name).to_string();
+        submodule
+            .update(true, Some(&mut update_options))
+            .map_err(|e| map_remote_error(e, &url, progress))?;
+
+        // Nested submodules are initialised from inside their parent
+        update_submodules(&submodule.open()?, progress)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,269 @@
+use crate::core::git_error::{GitError, GitResult};
+use crate::core::git_merge_operations::{clear_pending_squash, conflicted_paths, pending_squash};
+use crate::core::git_reset_operations::backup_before_hard_reset;
+use crate::models::git_repository::{
+    ConflictResolution, ConflictResolutionType, ConflictVersions, ResetResult,
+};
+use git2::{Oid, Repository as Git2Repository, RepositoryState, ResetType};
+use std::fs;
+use std::path::{Component, Path};
+use tracing::{debug, info, instrument, warn};
+
+// Index stages used by git for the three sides of a conflict
+const STAGE_ANCESTOR: i32 = 1;
+const STAGE_OURS: i32 = 2;
+const STAGE_THEIRS: i32 = 3;
+
+/// Extension trait for GitEngine to handle conflict resolution
+pub trait GitConflictOperations {
+    fn get_conflict_versions(&self, file_path: &str) -> GitResult<ConflictVersions>;
+    fn resolve_conflict(&self, resolution: &ConflictResolution) -> GitResult<()>;
+    fn abort_merge(&mut self) -> GitResult<ResetResult>;
+    fn continue_merge(&self, message: Option<&str>) -> GitResult<String>;
+}
+
+impl GitConflictOperations for Git2Repository {
+    #[instrument(skip(self))]
+    fn get_conflict_versions(&self, file_path: &str) -> GitResult<ConflictVersions> {
+        info!("Reading conflict versions");
+        let path_str = file_path.replace('\\', "/");
+        let path = Path::new(&path_str);
+        let index = self.index()?;
+
+        let mut is_binary = false;
+        let mut read_stage = |stage: i32| -> GitResult<Option<String>> {
+            match index.get_path(path, stage) {
+                Some(entry) => {
+                    let blob = self.find_blob(entry.id)?;
+                    is_binary |= blob.is_binary();
+                    Ok(Some(String::from_utf8_lossy(blob.content()).to_string()))
+                }
+                None => Ok(None),
+            }
+        };
+
+        let ancestor = read_stage(STAGE_ANCESTOR)?;
+        let ours = read_stage(STAGE_OURS)?;
+        let theirs = read_stage(STAGE_THEIRS)?;
+
+        if ancestor.is_none() && ours.is_none() && theirs.is_none() {
+            return Err(GitError::FileNotFound(path_str));
+        }
+
+        debug!(is_binary, "Conflict versions retrieved");
+        Ok(ConflictVersions {
+            path: path_str,
+            ancestor,
+            ours,
+            theirs,
+            is_binary,
+        })
+    }
+
+    /// Resolve one conflicted file. Choosing a side that deleted the file
+    /// deletes it; paths that are not in conflict are rejected.
+    #[instrument(skip(self, resolution), fields(path = %resolution.path))]
+    fn resolve_conflict(&self, resolution: &ConflictResolution) -> GitResult<()> {
+        info!("Resolving conflict");
+        let path_str = resolution.path.replace('\\', "/");
+        let path = relative_path(&path_str)?;
+        let mut index = self.index()?;
+
+        let in_conflict = [STAGE_ANCESTOR, STAGE_OURS, STAGE_THEIRS]
+            .iter()
+            .any(|&stage| index.get_path(path, stage).is_some());
+        if !in_conflict {
+            warn!("Path has no conflict entries");
+            return Err(GitError::OperationFailed(format!(
+                "'{}' is not in conflict",
+                path_str
+            )));
+        }
+
+        let content = match &resolution.resolution {
+            ConflictResolutionType::Ours => read_stage_blob(self, &index, path, STAGE_OURS)?,
+            ConflictResolutionType::Theirs => read_stage_blob(self, &index, path, STAGE_THEIRS)?,
+            ConflictResolutionType::Manual { content } => Some(content.as_bytes().to_vec()),
+        };
+
+        let repo_root = self.workdir().unwrap_or(self.path());
+        let abs_path = repo_root.join(path);
+
+        match content {
+            Some(bytes) => {
+                if let Some(parent) = abs_path.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                fs::write(&abs_path, bytes)?;
+                // Adding the path replaces the conflict stages with a resolved entry
+                index.add_path(path)?;
+            }
+            None => {
+                // The chosen side deleted the file
+                if abs_path.exists() {
+                    fs::remove_file(&abs_path)?;
+                }
+                index.remove_path(path)?;
+            }
+        }
+
+        index.write()?;
+        info!("Conflict resolved");
+        Ok(())
+    }
+
+    /// Abort a merge, squash merge, cherry-pick or revert in progress.
+    ///
+    /// Like a hard reset, the current commit is kept under `refs/navin/backup`
+    /// and uncommitted changes, conflicted files included, are stashed first.
+    #[instrument(skip(self))]
+    fn abort_merge(&mut self) -> GitResult<ResetResult> {
+        info!("Aborting merge");
+        ensure_merge_in_progress(self)?;
+
+        // A stash cannot hold conflicts, so stage conflicted files as they are on disk
+        let mut index = self.index()?;
+        if index.has_conflicts() {
+            let repo_root = self.workdir().unwrap_or(self.path()).to_path_buf();
+            for path in conflicted_paths(&index)? {
+                if repo_root.join(&path).exists() {
+                    index.add_path(Path::new(&path))?;
+                } else {
+                    index.remove_path(Path::new(&path))?;
+                }
+            }
+            index.write()?;
+        }
+
+        let (backup_ref, stash_sha) = backup_before_hard_reset(self, "aborting merge")?;
+        let head = self.head()?.peel_to_commit()?;
+        self.reset(head.as_object(), ResetType::Hard, None)?;
+        self.cleanup_state()?;
+        clear_pending_squash(self)?;
+
+        info!("Merge aborted");
+        Ok(ResetResult {
+            head_sha: head.id().to_string(),
+            backup_ref: Some(backup_ref),
+            stash_sha,
+        })
+    }
+
+    #[instrument(skip(self, message))]
+    fn continue_merge(&self, message: Option<&str>) -> GitResult<String> {
+        info!("Continuing merge");
+        ensure_merge_in_progress(self)?;
+        let mut index = self.index()?;
+        if index.has_conflicts() {
+            let conflicts = conflicted_paths(&index)?;
+            warn!(count = conflicts.len(), "Unresolved conflicts remain");
+            return Err(GitError::MergeConflict(conflicts.join(", ")));
+        }
+
+        // A squash has no MERGE_HEAD, so it is committed with HEAD as its only parent
+        let squash = pending_squash(self)?;
+        let merge_heads = read_merge_heads(self)?;
+        let message = match (message, &squash) {
+            (Some(message), _) => message.to_string(),
+            (None, Some(squash)) => squash.message.clone(),
+            (None, None) => self
+                .message()
+                .unwrap_or_else(|_| "Merge commit".to_string()),
+        };
+
+        let tree = self.find_tree(index.write_tree()?)?;
+        let signature = self.signature()?;
+        let head_commit = self.head()?.peel_to_commit()?;
+        if squash.is_some() && tree.id() == head_commit.tree_id() {
+            return Err(GitError::NoStagedChanges);
+        }
+
+        // A resolved cherry-pick keeps the author of the picked commit
+        let author = match self.state() {
+            RepositoryState::CherryPick | RepositoryState::CherryPickSequence => self
+                .find_reference("CHERRY_PICK_HEAD")?
+                .peel_to_commit()?
+                .author()
+                .to_owned(),
+            _ => signature.clone(),
+        };
+
+        let mut parents = vec![head_commit];
+        for oid in merge_heads {
+            parents.push(self.find_commit(oid)?);
+        }
+        let parent_refs: Vec<&git2::Commit> = parents.iter().collect();
+
+        let oid = self.commit(
+            Some("HEAD"),
+            &author,
+            &signature,
+            &message,
+            &tree,
+            &parent_refs,
+        )?;
+        self.cleanup_state()?;
+        clear_pending_squash(self)?;
+
+        info!(commit = %oid, "Merge completed");
+        Ok(oid.to_string())
+    }
+}
+
+// Helper functions
+
+/// Reject absolute paths and paths that climb out of the working tree
+fn relative_path(path: &str) -> GitResult<&Path> {
+    let path = Path::new(path);
+    let is_relative = path
+        .components()
+        .all(|component| matches!(component, Component::Normal(_) | Component::CurDir));
+    if path.as_os_str().is_empty() || !is_relative {
+        return Err(GitError::InvalidPath(path.display().to_string()));
+    }
+    Ok(path)
+}
+
+fn ensure_merge_in_progress(repo: &Git2Repository) -> GitResult<()> {
+    if pending_squash(repo)?.is_some() {
+        return Ok(());
+    }
+    match repo.state() {
+        RepositoryState::Merge
+        | RepositoryState::CherryPick
+        | RepositoryState::CherryPickSequence
+        | RepositoryState::Revert
+        | RepositoryState::RevertSequence => Ok(()),
+        state => {
+            warn!(?state, "No merge in progress");
+            Err(GitError::OperationFailed(
+                "No merge, squash, cherry-pick or revert is in progress".to_string(),
+            ))
+        }
+    }
+}
+
+fn read_stage_blob(
+    repo: &Git2Repository,
+    index: &git2::Index,
+    path: &Path,
+    stage: i32,
+) -> GitResult<Option<Vec<u8>>> {
+    match index.get_path(path, stage) {
+        Some(entry) => Ok(Some(repo.find_blob(entry.id)?.content().to_vec())),
+        None => Ok(None),
+    }
+}
+
+fn read_merge_heads(repo: &Git2Repository) -> GitResult<Vec<Oid>> {
+    let merge_head_path = repo.path().join("MERGE_HEAD");
+    if !merge_head_path.exists() {
+        return Ok(Vec::new());
+    }
+
+    fs::read_to_string(merge_head_path)?
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| Oid::from_str(line.trim()).map_err(GitError::from))
+        .collect()
+}
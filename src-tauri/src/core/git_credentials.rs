@@ -0,0 +1,249 @@
+use crate::core::git_error::{GitError, GitResult};
+use crate::core::git_progress::OperationProgress;
+use crate::models::git_repository::{CredentialInfo, CredentialKind, HostCredential};
+use git2::{Config, Cred, CredentialType, ErrorClass, ErrorCode, RemoteCallbacks};
+use std::path::Path;
+use tracing::{debug, info, instrument, warn};
+
+/// Keyring service under which per-host git credentials are stored
+const KEYRING_SERVICE: &str = "navin-git";
+
+/// Upper bound on credential requests for a single network operation
+const MAX_ATTEMPTS: usize = 8;
+
+/// Save the credential for a host in the OS keyring, replacing any existing one
+#[instrument(skip(credential))]
+pub fn save_host_credential(host: &str, credential: &HostCredential) -> GitResult<()> {
+    info!("Saving host credential");
+    let payload =
+        serde_json::to_string(credential).map_err(|e| GitError::CredentialStore(e.to_string()))?;
+    keyring_entry(host)?
+        .set_password(&payload)
+        .map_err(|e| GitError::CredentialStore(e.to_string()))?;
+    info!("Host credential saved");
+    Ok(())
+}
+
+/// Load the credential saved for a host, if there is one
+#[instrument]
+pub fn load_host_credential(host: &str) -> GitResult<Option<HostCredential>> {
+    debug!("Loading host credential");
+    match keyring_entry(host)?.get_password() {
+        Ok(payload) => serde_json::from_str(&payload)
+            .map(Some)
+            .map_err(|e| GitError::CredentialStore(e.to_string())),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(GitError::CredentialStore(e.to_string())),
+    }
+}
+
+/// Describe the credential saved for a host without exposing the secret
+pub fn get_host_credential_info(host: &str) -> GitResult<Option<CredentialInfo>> {
+    Ok(load_host_credential(host)?.map(|credential| {
+        let (username, kind) = match credential {
+            HostCredential::Token { username, .. } => (username, CredentialKind::Token),
+            HostCredential::SshKey { username, .. } => (username, CredentialKind::SshKey),
+        };
+        CredentialInfo {
+            host: host.to_string(),
+            username,
+            kind,
+        }
+    }))
+}
+
+/// Remove the credential saved for a host; removing a missing one is not an error
+#[instrument]
+pub fn delete_host_credential(host: &str) -> GitResult<()> {
+    info!("Deleting host credential");
+    match keyring_entry(host)?.delete_credential() {
+        Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+        Err(e) => Err(GitError::CredentialStore(e.to_string())),
+    }
+}
+
+/// Build remote callbacks that authenticate with saved credentials, the SSH agent
+/// and the configured git credential helpers, in that order.
+pub(crate) fn credential_callbacks<'a>(repo: &git2::Repository) -> RemoteCallbacks<'a> {
+    credential_callbacks_with_config(repo.config().or_else(|_| Config::open_default()).ok())
+}
+
+/// Same as `credential_callbacks`, for operations that have no repository yet
+pub(crate) fn credential_callbacks_with_config<'a>(config: Option<Config>) -> RemoteCallbacks<'a> {
+    let mut provider = CredentialProvider::new(config);
+
+    let mut callbacks = RemoteCallbacks::new();
+    callbacks.credentials(move |url, username_from_url, allowed_types| {
+        provider.next_credential(url, username_from_url, allowed_types)
+    });
+    callbacks
+}
+
+/// Turn a network error into `Cancelled` when the operation was cancelled, or
+/// `AuthenticationFailed` when credentials were rejected
+pub(crate) fn map_remote_error(
+    error: git2::Error,
+    url: &str,
+    progress: Option<&OperationProgress>,
+) -> GitError {
+    if progress.is_some_and(OperationProgress::is_cancelled) {
+        GitError::Cancelled
+    } else if error.code() == ErrorCode::Auth {
+        warn!(url, "Authentication failed");
+        GitError::AuthenticationFailed(url.to_string())
+    } else {
+        GitError::from(error)
+    }
+}
+
+/// Extract the host from an https, ssh or scp-style (`git@host:path`) remote URL
+pub fn host_from_url(url: &str) -> Option<String> {
+    let rest = match url.split_once("://") {
+        Some((_, rest)) => rest,
+        None => url.split_once(':')?.0,
+    };
+    let authority = rest.split('/').next()?;
+    let host_port = authority.rsplit('@').next()?;
+    let host = host_port.split(':').next()?;
+    if host.is_empty() {
+        None
+    } else {
+        Some(host.to_lowercase())
+    }
+}
+
+// Helper functions
+
+fn keyring_entry(host: &str) -> GitResult<keyring::Entry> {
+    keyring::Entry::new(KEYRING_SERVICE, &host.to_lowercase())
+        .map_err(|e| GitError::CredentialStore(e.to_string()))
+}
+
+/// Hands out each credential source at most once per operation.
+///
+/// libgit2 calls the credentials callback again whenever the previous credential
+/// is rejected, so returning the same one would retry forever.
+struct CredentialProvider {
+    config: Option<Config>,
+    saved: Option<Option<HostCredential>>,
+    attempts: usize,
+    tried_saved_key: bool,
+    tried_agent: bool,
+    tried_saved_token: bool,
+    tried_helper: bool,
+    tried_default: bool,
+}
+
+impl CredentialProvider {
+    fn new(config: Option<Config>) -> Self {
+        Self {
+            config,
+            saved: None,
+            attempts: 0,
+            tried_saved_key: false,
+            tried_agent: false,
+            tried_saved_token: false,
+            tried_helper: false,
+            tried_default: false,
+        }
+    }
+
+    fn next_credential(
+        &mut self,
+        url: &str,
+        username_from_url: Option<&str>,
+        allowed_types: CredentialType,
+    ) -> Result<Cred, git2::Error> {
+        self.attempts += 1;
+        if self.attempts > MAX_ATTEMPTS {
+            return Err(auth_error(url));
+        }
+
+        let saved = self.saved_credential(url);
+        let saved_username = match &saved {
+            Some(HostCredential::Token { username, .. })
+            | Some(HostCredential::SshKey { username, .. }) => Some(username.clone()),
+            None => None,
+        };
+        let username = username_from_url
+            .map(String::from)
+            .or(saved_username)
+            .unwrap_or_else(|| "git".to_string());
+
+        if allowed_types.contains(CredentialType::USERNAME) {
+            return Cred::username(&username);
+        }
+
+        if allowed_types.contains(CredentialType::SSH_KEY) {
+            if !self.tried_saved_key {
+                self.tried_saved_key = true;
+                if let Some(HostCredential::SshKey {
+                    private_key_path,
+                    public_key_path,
+                    passphrase,
+                    ..
+                }) = &saved
+                {
+                    debug!("Trying saved SSH key");
+                    return Cred::ssh_key(
+                        &username,
+                        public_key_path.as_deref().map(Path::new),
+                        Path::new(private_key_path),
+                        passphrase.as_deref(),
+                    );
+                }
+            }
+            if !self.tried_agent {
+                self.tried_agent = true;
+                debug!("Trying SSH agent");
+                return Cred::ssh_key_from_agent(&username);
+            }
+        }
+
+        if allowed_types.contains(CredentialType::USER_PASS_PLAINTEXT) {
+            if !self.tried_saved_token {
+                self.tried_saved_token = true;
+                if let Some(HostCredential::Token { username, token }) = &saved {
+                    debug!("Trying saved token");
+                    return Cred::userpass_plaintext(username, token);
+                }
+            }
+            if !self.tried_helper {
+                self.tried_helper = true;
+                if let Some(config) = &self.config {
+                    debug!("Trying git credential helper");
+                    if let Ok(cred) = Cred::credential_helper(config, url, username_from_url) {
+                        return Ok(cred);
+                    }
+                }
+            }
+        }
+
+        if allowed_types.contains(CredentialType::DEFAULT) && !self.tried_default {
+            self.tried_default = true;
+            return Cred::default();
+        }
+
+        Err(auth_error(url))
+    }
+
+    fn saved_credential(&mut self, url: &str) -> Option<HostCredential> {
+        self.saved
+            .get_or_insert_with(|| {
+                let host = host_from_url(url)?;
+                load_host_credential(&host).unwrap_or_else(|e| {
+                    warn!(host, "Could not read saved credential: {}", e);
+                    None
+                })
+            })
+            .clone()
+    }
+}
+
+fn auth_error(url: &str) -> git2::Error {
+    git2::Error::new(
+        ErrorCode::Auth,
+        ErrorClass::Net,
+        format!("No more credentials to try for {}", url),
+    )
+}
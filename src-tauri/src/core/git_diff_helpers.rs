@@ -1,116 +1,396 @@
 use crate::core::git_error::GitResult;
-use crate::models::git_repository::{DiffHunk, DiffLine, DiffLineType, FileDiff, FileStatusType};
-use git2::Diff as Git2Diff;
+use crate::models::git_repository::{
+    DiffHunk, DiffLine, DiffLineType, DiffRequestOptions, FileDiff, FileStatusType, WhitespaceMode,
+    WordRange,
+};
+use git2::{Diff as Git2Diff, DiffDelta, DiffFindOptions, DiffOptions, Patch};
 
-/// Convert a git2 diff to a vector of FileDiff structures
-pub fn diff_to_file_diffs(diff: &Git2Diff) -> GitResult<Vec<FileDiff>> {
-    let mut file_diffs = Vec::new();
-
-    diff.foreach(
-        &mut |delta, _progress| {
-            let old_path = delta
-                .old_file()
-                .path()
-                .map(|p| p.to_string_lossy().to_string());
-            let new_path = delta
-                .new_file()
-                .path()
-                .map(|p| p.to_string_lossy().to_string());
-
-            let status = match delta.status() {
-                git2::Delta::Added => FileStatusType::Added,
-                git2::Delta::Deleted => FileStatusType::Deleted,
-                git2::Delta::Modified => FileStatusType::Modified,
-                git2::Delta::Renamed => FileStatusType::Renamed {
-                    old_path: old_path.clone().unwrap_or_default(),
-                },
-                git2::Delta::Copied => FileStatusType::Copied,
-                _ => FileStatusType::Modified,
-            };
+/// Longest pair of lines, in tokens, that word diff compares
+const MAX_WORD_DIFF_TOKENS: usize = 500;
 
-            file_diffs.push(FileDiff {
-                old_path,
-                new_path,
-                status,
-                hunks: Vec::new(), // Will be populated below
-                binary: delta.old_file().is_binary() || delta.new_file().is_binary(),
-                additions: 0,
-                deletions: 0,
-            });
+/// Size caps applied to each file of a diff; hunks past a cap are left out and
+/// the file is marked truncated
+#[derive(Debug, Clone, Copy)]
+pub struct DiffLimits {
+    pub max_hunks: usize,
+    pub max_lines: usize,
+    pub max_bytes: usize,
+}
 
-            true
-        },
-        None,
-        None,
-        None,
-    )?;
-
-    // Get detailed hunks and lines - collect first, then update
-    let mut all_hunks: Vec<(Vec<DiffHunk>, usize, usize)> = Vec::new();
-
-    for _ in 0..file_diffs.len() {
-        let mut hunks = Vec::new();
-        let mut additions = 0;
-        let mut deletions = 0;
-
-        diff.print(git2::DiffFormat::Patch, |_delta, hunk, line| {
-            // Add hunk if needed
-            if let Some(hunk_header) = hunk {
-                if hunks.is_empty()
-                    || hunks.last().map(|h: &DiffHunk| &h.header)
-                        != Some(&String::from_utf8_lossy(hunk_header.header()).to_string())
-                {
-                    hunks.push(DiffHunk {
-                        old_start: hunk_header.old_start() as usize,
-                        old_lines: hunk_header.old_lines() as usize,
-                        new_start: hunk_header.new_start() as usize,
-                        new_lines: hunk_header.new_lines() as usize,
-                        header: String::from_utf8_lossy(hunk_header.header()).to_string(),
-                        lines: Vec::new(),
-                    });
-                }
-            }
+impl Default for DiffLimits {
+    fn default() -> Self {
+        Self {
+            max_hunks: 200,
+            max_lines: 5_000,
+            max_bytes: 512 * 1024,
+        }
+    }
+}
 
-            // Add line to current hunk
-            if let Some(current_hunk) = hunks.last_mut() {
-                let origin = match line.origin() {
-                    '+' => {
-                        additions += 1;
-                        DiffLineType::Addition
-                    }
-                    '-' => {
-                        deletions += 1;
-                        DiffLineType::Deletion
-                    }
-                    ' ' => DiffLineType::Context,
-                    'F' => DiffLineType::FileHeader,
-                    'H' => DiffLineType::HunkHeader,
-                    'B' => DiffLineType::Binary,
-                    _ => DiffLineType::Context,
-                };
-
-                current_hunk.lines.push(DiffLine {
-                    origin,
-                    content: String::from_utf8_lossy(line.content()).to_string(),
-                    old_lineno: line.old_lineno().map(|n| n as usize),
-                    new_lineno: line.new_lineno().map(|n| n as usize),
-                });
-            }
+/// Build git2 diff options for a request; the caller adds pathspecs
+pub fn build_diff_options(request: &DiffRequestOptions) -> DiffOptions {
+    let mut options = DiffOptions::new();
+    if let Some(context_lines) = request.context_lines {
+        options.context_lines(context_lines);
+    }
+    match request.whitespace {
+        WhitespaceMode::Show => {}
+        WhitespaceMode::IgnoreAtEol => {
+            options.ignore_whitespace_eol(true);
+        }
+        WhitespaceMode::IgnoreChanges => {
+            options.ignore_whitespace_change(true);
+        }
+        WhitespaceMode::IgnoreAll => {
+            options.ignore_whitespace(true);
+        }
+    }
+    options
+}
 
-            true
-        })?;
+/// Run rename and copy detection on a diff when the request asks for it
+pub fn find_similar(diff: &mut Git2Diff, request: &DiffRequestOptions) -> GitResult<()> {
+    if request.rename_threshold.is_none() && request.copy_threshold.is_none() {
+        return Ok(());
+    }
 
-        all_hunks.push((hunks, additions, deletions));
+    let mut find_options = DiffFindOptions::new();
+    if let Some(threshold) = request.rename_threshold {
+        find_options.renames(true).rename_threshold(threshold);
+    }
+    if let Some(threshold) = request.copy_threshold {
+        find_options.copies(true).copy_threshold(threshold);
+    }
+    if matches!(
+        request.whitespace,
+        WhitespaceMode::IgnoreChanges | WhitespaceMode::IgnoreAll
+    ) {
+        find_options.ignore_whitespace(true);
     }
+    diff.find_similar(Some(&mut find_options))?;
+    Ok(())
+}
 
-    // Now update all file_diffs with collected data
-    for (i, (hunks, additions, deletions)) in all_hunks.into_iter().enumerate() {
-        if let Some(file_diff) = file_diffs.get_mut(i) {
-            file_diff.hunks = hunks;
+/// Convert a diff as requested: capped unless `full` is set, with word ranges
+/// when asked for
+pub fn diff_to_file_diffs_with(
+    diff: &Git2Diff,
+    request: &DiffRequestOptions,
+) -> GitResult<Vec<FileDiff>> {
+    let limits = if request.full {
+        None
+    } else {
+        Some(DiffLimits::default())
+    };
+    let mut file_diffs = convert_diff(diff, limits.as_ref())?;
+    if request.word_diff {
+        for hunk in file_diffs.iter_mut().flat_map(|f| f.hunks.iter_mut()) {
+            add_word_ranges(hunk);
+        }
+    }
+    Ok(file_diffs)
+}
+
+/// Convert a git2 diff to a vector of FileDiff structures, capping large files
+pub fn diff_to_file_diffs(diff: &Git2Diff) -> GitResult<Vec<FileDiff>> {
+    convert_diff(diff, Some(&DiffLimits::default()))
+}
+
+/// Convert each delta from its own patch, so the diff is walked once.
+///
+/// Only whole hunks are returned: a hunk that would cross a limit is dropped
+/// together with the rest of the file, which keeps returned hunks stageable.
+fn convert_diff(diff: &Git2Diff, limits: Option<&DiffLimits>) -> GitResult<Vec<FileDiff>> {
+    let mut file_diffs = Vec::with_capacity(diff.deltas().len());
+
+    for index in 0..diff.deltas().len() {
+        let Some(delta) = diff.get_delta(index) else {
+            continue;
+        };
+        let mut file_diff = delta_to_file_diff(&delta);
+
+        if let Some(patch) = Patch::from_diff(diff, index)? {
+            let (_, additions, deletions) = patch.line_stats()?;
             file_diff.additions = additions;
             file_diff.deletions = deletions;
+            file_diff.binary |= patch.delta().flags().is_binary();
+
+            let (hunks, truncated) = patch_hunks(&patch, limits)?;
+            file_diff.hunks = hunks;
+            file_diff.truncated = truncated;
         }
+
+        file_diffs.push(file_diff);
     }
 
     Ok(file_diffs)
 }
+
+fn delta_to_file_diff(delta: &DiffDelta) -> FileDiff {
+    let old_path = delta
+        .old_file()
+        .path()
+        .map(|p| p.to_string_lossy().to_string());
+    let new_path = delta
+        .new_file()
+        .path()
+        .map(|p| p.to_string_lossy().to_string());
+
+    let status = match delta.status() {
+        git2::Delta::Added => FileStatusType::Added,
+        git2::Delta::Deleted => FileStatusType::Deleted,
+        git2::Delta::Modified => FileStatusType::Modified,
+        git2::Delta::Renamed => FileStatusType::Renamed {
+            old_path: old_path.clone().unwrap_or_default(),
+        },
+        git2::Delta::Copied => FileStatusType::Copied,
+        _ => FileStatusType::Modified,
+    };
+
+    FileDiff {
+        old_path,
+        new_path,
+        status,
+        hunks: Vec::new(),
+        binary: delta.old_file().is_binary() || delta.new_file().is_binary(),
+        additions: 0,
+        deletions: 0,
+        truncated: false,
+    }
+}
+
+/// Collect the hunks of a patch, stopping before the first one that would
+/// exceed the limits
+fn patch_hunks(patch: &Patch, limits: Option<&DiffLimits>) -> GitResult<(Vec<DiffHunk>, bool)> {
+    let mut hunks = Vec::new();
+    let mut total_lines = 0;
+    let mut total_bytes = 0;
+
+    for hunk_index in 0..patch.num_hunks() {
+        if limits.is_some_and(|limits| hunks.len() >= limits.max_hunks) {
+            return Ok((hunks, true));
+        }
+
+        let (hunk_header, line_count) = patch.hunk(hunk_index)?;
+        let header = String::from_utf8_lossy(hunk_header.header()).to_string();
+        let mut lines = Vec::with_capacity(line_count + 1);
+        let mut bytes = header.len();
+        lines.push(DiffLine {
+            origin: DiffLineType::HunkHeader,
+            content: header.clone(),
+            old_lineno: None,
+            new_lineno: None,
+            word_ranges: Vec::new(),
+        });
+
+        for line_index in 0..line_count {
+            let line = patch.line_in_hunk(hunk_index, line_index)?;
+            let origin = match line.origin() {
+                '+' => DiffLineType::Addition,
+                '-' => DiffLineType::Deletion,
+                'F' => DiffLineType::FileHeader,
+                'H' => DiffLineType::HunkHeader,
+                'B' => DiffLineType::Binary,
+                _ => DiffLineType::Context,
+            };
+            let content = String::from_utf8_lossy(line.content()).to_string();
+            bytes += content.len();
+            lines.push(DiffLine {
+                origin,
+                content,
+                old_lineno: line.old_lineno().map(|n| n as usize),
+                new_lineno: line.new_lineno().map(|n| n as usize),
+                word_ranges: Vec::new(),
+            });
+        }
+
+        total_lines += lines.len();
+        total_bytes += bytes;
+        if limits
+            .is_some_and(|limits| total_lines > limits.max_lines || total_bytes > limits.max_bytes)
+        {
+            return Ok((hunks, true));
+        }
+
+        hunks.push(DiffHunk {
+            old_start: hunk_header.old_start() as usize,
+            old_lines: hunk_header.old_lines() as usize,
+            new_start: hunk_header.new_start() as usize,
+            new_lines: hunk_header.new_lines() as usize,
+            header,
+            lines,
+        });
+    }
+
+    Ok((hunks, false))
+}
+
+/// Mark the changed words of each removed line and the added line that replaces it.
+///
+/// Within a run of changes the n-th removed line is paired with the n-th added
+/// line; lines without a partner are left without ranges.
+fn add_word_ranges(hunk: &mut DiffHunk) {
+    let mut index = 0;
+    while index < hunk.lines.len() {
+        let removed_start = index;
+        while index < hunk.lines.len() && matches!(hunk.lines[index].origin, DiffLineType::Deletion)
+        {
+            index += 1;
+        }
+        let added_start = index;
+        while index < hunk.lines.len() && matches!(hunk.lines[index].origin, DiffLineType::Addition)
+        {
+            index += 1;
+        }
+        if added_start == removed_start && index == added_start {
+            index += 1;
+            continue;
+        }
+
+        let pairs = (added_start - removed_start).min(index - added_start);
+        for offset in 0..pairs {
+            let (old_ranges, new_ranges) = word_ranges(
+                &hunk.lines[removed_start + offset].content,
+                &hunk.lines[added_start + offset].content,
+            );
+            hunk.lines[removed_start + offset].word_ranges = old_ranges;
+            hunk.lines[added_start + offset].word_ranges = new_ranges;
+        }
+    }
+}
+
+/// Compare two lines word by word, returning the changed spans of each
+fn word_ranges(old: &str, new: &str) -> (Vec<WordRange>, Vec<WordRange>) {
+    let old_tokens = tokenize(old.trim_end_matches(['\n', '\r']));
+    let new_tokens = tokenize(new.trim_end_matches(['\n', '\r']));
+    if old_tokens.len() > MAX_WORD_DIFF_TOKENS || new_tokens.len() > MAX_WORD_DIFF_TOKENS {
+        return (Vec::new(), Vec::new());
+    }
+
+    // Longest common subsequence of tokens; whatever is not in it changed
+    let (n, m) = (old_tokens.len(), new_tokens.len());
+    let mut lengths = vec![vec![0u16; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lengths[i][j] = if old_tokens[i].text == new_tokens[j].text {
+                lengths[i + 1][j + 1] + 1
+            } else {
+                lengths[i + 1][j].max(lengths[i][j + 1])
+            };
+        }
+    }
+
+    let mut old_ranges = Vec::new();
+    let mut new_ranges = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < n || j < m {
+        if i < n && j < m && old_tokens[i].text == new_tokens[j].text {
+            i += 1;
+            j += 1;
+        } else if j == m || (i < n && lengths[i + 1][j] >= lengths[i][j + 1]) {
+            push_range(&mut old_ranges, &old_tokens[i]);
+            i += 1;
+        } else {
+            push_range(&mut new_ranges, &new_tokens[j]);
+            j += 1;
+        }
+    }
+    (old_ranges, new_ranges)
+}
+
+struct Token<'a> {
+    text: &'a str,
+    start: usize,
+    end: usize,
+}
+
+/// Split a line into words, whitespace runs and single punctuation characters,
+/// with character offsets
+fn tokenize(line: &str) -> Vec<Token<'_>> {
+    let class = |c: char| {
+        if c.is_alphanumeric() || c == '_' {
+            0
+        } else if c.is_whitespace() {
+            1
+        } else {
+            2
+        }
+    };
+
+    let mut tokens: Vec<Token> = Vec::new();
+    let mut previous_class = None;
+    for (chars, (byte, c)) in line.char_indices().enumerate() {
+        let current = class(c);
+        match tokens.last_mut() {
+            Some(token) if previous_class == Some(current) && current != 2 => {
+                token.text = &line[byte - token.text.len()..byte + c.len_utf8()];
+                token.end = chars + 1;
+            }
+            _ => tokens.push(Token {
+                text: &line[byte..byte + c.len_utf8()],
+                start: chars,
+                end: chars + 1,
+            }),
+        }
+        previous_class = Some(current);
+    }
+    tokens
+}
+
+/// Add a token to a range list, extending the last range when they touch
+fn push_range(ranges: &mut Vec<WordRange>, token: &Token) {
+    match ranges.last_mut() {
+        Some(last) if last.end == token.start => last.end = token.end,
+        _ => ranges.push(WordRange {
+            start: token.start,
+            end: token.end,
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn range(start: usize, end: usize) -> WordRange {
+        WordRange { start, end }
+    }
+
+    #[test]
+    fn marks_a_single_changed_word() {
+        let (old, new) = word_ranges("let value = old;\n", "let value = new;\n");
+        assert_eq!(old, vec![range(12, 15)]);
+        assert_eq!(new, vec![range(12, 15)]);
+    }
+
+    #[test]
+    fn splits_punctuation_into_single_tokens() {
+        let texts: Vec<&str> = tokenize("f(a,b);").iter().map(|token| token.text).collect();
+        assert_eq!(texts, ["f", "(", "a", ",", "b", ")", ";"]);
+
+        let (old, new) = word_ranges("call(a, b)", "call(a; b)");
+        assert_eq!(old, vec![range(6, 7)]);
+        assert_eq!(new, vec![range(6, 7)]);
+    }
+
+    #[test]
+    fn counts_offsets_in_characters() {
+        // "é" is two bytes and "😀" is four bytes and two UTF-16 code units
+        let (old, new) = word_ranges("café 😀 naïve", "café 😀 naive");
+        assert_eq!(old, vec![range(7, 12)]);
+        assert_eq!(new, vec![range(7, 12)]);
+
+        let tokens = tokenize("😀 é");
+        assert_eq!(
+            (tokens[2].text, tokens[2].start, tokens[2].end),
+            ("é", 2, 3)
+        );
+    }
+
+    #[test]
+    fn skips_lines_over_the_token_limit() {
+        let long_line = "a ".repeat(MAX_WORD_DIFF_TOKENS);
+        assert!(tokenize(&long_line).len() > MAX_WORD_DIFF_TOKENS);
+        assert_eq!(word_ranges(&long_line, "a b"), (Vec::new(), Vec::new()));
+    }
+}
@@ -1,46 +1,76 @@
-use crate::core::git_diff_helpers::diff_to_file_diffs;
+use crate::core::git_diff_helpers::{build_diff_options, diff_to_file_diffs_with, find_similar};
 use crate::core::git_error::GitResult;
-use crate::models::git_repository::{FileDiff, FileStatusType};
+use crate::models::git_repository::{DiffRequestOptions, FileDiff, FileStatusType};
 use git2::Repository as Git2Repository;
 use std::path::Path;
 use tracing::{debug, info, instrument};
 
 /// Extension trait for diff operations
 pub trait GitDiffOperations {
-    fn get_file_diff_unstaged<P: AsRef<Path>>(&self, path: P) -> GitResult<FileDiff>;
-    fn get_file_diff_staged<P: AsRef<Path>>(&self, path: P) -> GitResult<FileDiff>;
-    fn get_diff_between_commits(&self, commit1: &str, commit2: &str) -> GitResult<Vec<FileDiff>>;
+    fn get_file_diff_unstaged<P: AsRef<Path>>(
+        &self,
+        path: P,
+        options: &DiffRequestOptions,
+    ) -> GitResult<FileDiff>;
+    fn get_file_diff_staged<P: AsRef<Path>>(
+        &self,
+        path: P,
+        options: &DiffRequestOptions,
+    ) -> GitResult<FileDiff>;
+    fn get_diff_between_commits(
+        &self,
+        commit1: &str,
+        commit2: &str,
+        options: &DiffRequestOptions,
+    ) -> GitResult<Vec<FileDiff>>;
+    fn get_commit_file_diff(
+        &self,
+        from: Option<&str>,
+        to: &str,
+        file_path: &str,
+        options: &DiffRequestOptions,
+    ) -> GitResult<FileDiff>;
 }
 
 impl GitDiffOperations for Git2Repository {
-    #[instrument(skip(self, path), fields(path = %path.as_ref().display()))]
-    fn get_file_diff_unstaged<P: AsRef<Path>>(&self, path: P) -> GitResult<FileDiff> {
+    #[instrument(skip(self, path, options), fields(path = %path.as_ref().display()))]
+    fn get_file_diff_unstaged<P: AsRef<Path>>(
+        &self,
+        path: P,
+        options: &DiffRequestOptions,
+    ) -> GitResult<FileDiff> {
         let path = path.as_ref();
         info!("Getting unstaged diff for file");
 
-        let mut diff_options = git2::DiffOptions::new();
+        let mut diff_options = build_diff_options(options);
         diff_options.pathspec(path);
 
-        let diff = self.diff_index_to_workdir(None, Some(&mut diff_options))?;
-        let file_diffs = diff_to_file_diffs(&diff)?;
+        let mut diff = self.diff_index_to_workdir(None, Some(&mut diff_options))?;
+        find_similar(&mut diff, options)?;
+        let file_diffs = diff_to_file_diffs_with(&diff, options)?;
 
         file_diffs.into_iter().next().ok_or_else(|| {
             crate::core::git_error::GitError::FileNotFound(path.to_string_lossy().to_string())
         })
     }
 
-    #[instrument(skip(self, path), fields(path = %path.as_ref().display()))]
-    fn get_file_diff_staged<P: AsRef<Path>>(&self, path: P) -> GitResult<FileDiff> {
+    #[instrument(skip(self, path, options), fields(path = %path.as_ref().display()))]
+    fn get_file_diff_staged<P: AsRef<Path>>(
+        &self,
+        path: P,
+        options: &DiffRequestOptions,
+    ) -> GitResult<FileDiff> {
         let path = path.as_ref();
         info!("Getting staged diff for file");
 
         let head = self.head()?.peel_to_tree()?;
 
-        let mut diff_options = git2::DiffOptions::new();
+        let mut diff_options = build_diff_options(options);
         diff_options.pathspec(path);
 
-        let diff = self.diff_tree_to_index(Some(&head), None, Some(&mut diff_options))?;
-        let file_diffs = diff_to_file_diffs(&diff)?;
+        let mut diff = self.diff_tree_to_index(Some(&head), None, Some(&mut diff_options))?;
+        find_similar(&mut diff, options)?;
+        let file_diffs = diff_to_file_diffs_with(&diff, options)?;
 
         // If no diff found, return empty diff
         if file_diffs.is_empty() {
@@ -52,14 +82,20 @@ impl GitDiffOperations for Git2Repository {
                 binary: false,
                 additions: 0,
                 deletions: 0,
+                truncated: false,
             });
         }
 
         Ok(file_diffs.into_iter().next().unwrap())
     }
 
-    #[instrument(skip(self))]
-    fn get_diff_between_commits(&self, commit1: &str, commit2: &str) -> GitResult<Vec<FileDiff>> {
+    #[instrument(skip(self, options))]
+    fn get_diff_between_commits(
+        &self,
+        commit1: &str,
+        commit2: &str,
+        options: &DiffRequestOptions,
+    ) -> GitResult<Vec<FileDiff>> {
         info!(commit1, commit2, "Getting diff between commits");
 
         let oid1 = git2::Oid::from_str(commit1)?;
@@ -68,8 +104,11 @@ impl GitDiffOperations for Git2Repository {
         let tree1 = self.find_commit(oid1)?.tree()?;
         let tree2 = self.find_commit(oid2)?.tree()?;
 
-        let diff = self.diff_tree_to_tree(Some(&tree1), Some(&tree2), None)?;
-        let file_diffs = diff_to_file_diffs(&diff)?;
+        let mut diff_options = build_diff_options(options);
+        let mut diff =
+            self.diff_tree_to_tree(Some(&tree1), Some(&tree2), Some(&mut diff_options))?;
+        find_similar(&mut diff, options)?;
+        let file_diffs = diff_to_file_diffs_with(&diff, options)?;
 
         debug!(
             file_count = file_diffs.len(),
@@ -77,4 +116,45 @@ impl GitDiffOperations for Git2Repository {
         );
         Ok(file_diffs)
     }
+
+    /// Every hunk of one file between two commits, or between a commit and its
+    /// first parent when `from` is unset. Loads files a commit diff truncated.
+    #[instrument(skip(self, options))]
+    fn get_commit_file_diff(
+        &self,
+        from: Option<&str>,
+        to: &str,
+        file_path: &str,
+        options: &DiffRequestOptions,
+    ) -> GitResult<FileDiff> {
+        info!("Getting full file diff");
+
+        let to_commit = self.find_commit(git2::Oid::from_str(to)?)?;
+        let from_tree = match from {
+            Some(from) => Some(self.find_commit(git2::Oid::from_str(from)?)?.tree()?),
+            None if to_commit.parent_count() > 0 => Some(to_commit.parent(0)?.tree()?),
+            None => None,
+        };
+
+        let mut diff_options = build_diff_options(options);
+        diff_options.pathspec(file_path);
+
+        let mut diff = self.diff_tree_to_tree(
+            from_tree.as_ref(),
+            Some(&to_commit.tree()?),
+            Some(&mut diff_options),
+        )?;
+        find_similar(&mut diff, options)?;
+
+        let options = DiffRequestOptions {
+            full: true,
+            ..options.clone()
+        };
+        let file_diffs = diff_to_file_diffs_with(&diff, &options)?;
+
+        file_diffs
+            .into_iter()
+            .next()
+            .ok_or_else(|| crate::core::git_error::GitError::FileNotFound(file_path.to_string()))
+    }
 }
@@ -0,0 +1,374 @@
+use crate::core::git_error::{GitError, GitResult};
+use crate::core::git_reset_operations::BACKUP_REF_PREFIX;
+use crate::models::git_repository::{DiscardResult, RestoreSource};
+use chrono::Utc;
+use git2::{IndexEntry, IndexTime, Repository as Git2Repository, Status, StatusOptions};
+use std::fs;
+use std::path::Path;
+use tracing::{debug, info, instrument};
+
+/// Namespace inside `BACKUP_REF_PREFIX` for files saved before a discard or clean
+const DISCARD_BACKUP_NAMESPACE: &str = "discard";
+
+// File modes recorded in backup trees
+const MODE_FILE: u32 = 0o100644;
+const MODE_EXECUTABLE: u32 = 0o100755;
+const MODE_SYMLINK: u32 = 0o120000;
+
+/// Extension trait for GitEngine to throw away local changes.
+///
+/// Every destructive call first records the current working tree contents of the
+/// affected files as a commit under `refs/navin/backup/discard`, so a mis-click
+/// can be undone with `restore_discard_backup`.
+pub trait GitDiscardOperations {
+    fn discard_changes(&self, paths: &[String], source: RestoreSource) -> GitResult<DiscardResult>;
+    fn clean_untracked(&self, paths: &[String], dry_run: bool) -> GitResult<DiscardResult>;
+    fn restore_discard_backup(&self, backup_ref: &str) -> GitResult<Vec<String>>;
+}
+
+impl GitDiscardOperations for Git2Repository {
+    /// Restore files from the index (unstaged changes only) or from HEAD
+    #[instrument(skip(self))]
+    fn discard_changes(&self, paths: &[String], source: RestoreSource) -> GitResult<DiscardResult> {
+        info!("Discarding changes");
+        let mut targets = Vec::new();
+        for path in paths {
+            let path_str = path.replace('\\', "/");
+            let status = self.status_file(Path::new(&path_str))?;
+            let worktree_changed = status.intersects(
+                Status::WT_MODIFIED
+                    | Status::WT_DELETED
+                    | Status::WT_TYPECHANGE
+                    | Status::WT_RENAMED,
+            );
+            let index_changed = status.intersects(
+                Status::INDEX_NEW
+                    | Status::INDEX_MODIFIED
+                    | Status::INDEX_DELETED
+                    | Status::INDEX_TYPECHANGE
+                    | Status::INDEX_RENAMED,
+            );
+
+            let discardable = match source {
+                RestoreSource::Index => worktree_changed,
+                RestoreSource::Head => worktree_changed || index_changed,
+            };
+            if status.is_wt_new() && !index_changed {
+                return Err(GitError::OperationFailed(format!(
+                    "'{}' is untracked; clean it instead",
+                    path_str
+                )));
+            }
+            if !discardable {
+                return Err(GitError::OperationFailed(format!(
+                    "'{}' has no changes to discard",
+                    path_str
+                )));
+            }
+            targets.push(path_str);
+        }
+
+        let backup_ref = backup_workdir_files(self, &targets, "discarding changes")?;
+
+        match source {
+            RestoreSource::Index => {
+                let mut checkout = checkout_paths(&targets);
+                self.checkout_index(None, Some(&mut checkout))?;
+            }
+            RestoreSource::Head => {
+                let head = self.head()?.peel_to_commit()?;
+                let head_tree = head.tree()?;
+                let (in_head, added): (Vec<&String>, Vec<&String>) = targets
+                    .iter()
+                    .partition(|path| head_tree.get_path(Path::new(path.as_str())).is_ok());
+
+                if !in_head.is_empty() {
+                    self.reset_default(Some(head.as_object()), in_head.iter().map(|p| p.as_str()))?;
+                    let mut checkout = checkout_paths(&in_head);
+                    self.checkout_head(Some(&mut checkout))?;
+                }
+
+                // Files added since HEAD are dropped from the index and the working tree
+                if !added.is_empty() {
+                    let repo_root = self.workdir().unwrap_or(self.path());
+                    let mut index = self.index()?;
+                    for path in &added {
+                        index.remove_path(Path::new(path.as_str()))?;
+                        let abs_path = repo_root.join(path.as_str());
+                        if abs_path.is_file() {
+                            fs::remove_file(&abs_path)?;
+                        }
+                    }
+                    index.write()?;
+                }
+            }
+        }
+
+        info!(count = targets.len(), "Changes discarded");
+        Ok(DiscardResult {
+            paths: targets,
+            backup_ref,
+            dry_run: false,
+        })
+    }
+
+    /// Delete untracked files, or every untracked file under the given directories.
+    ///
+    /// Ignored and tracked files are never touched. With `dry_run` set nothing is
+    /// deleted and the files that would be removed are returned.
+    #[instrument(skip(self))]
+    fn clean_untracked(&self, paths: &[String], dry_run: bool) -> GitResult<DiscardResult> {
+        info!("Cleaning untracked files");
+        let mut opts = StatusOptions::new();
+        opts.include_untracked(true)
+            .recurse_untracked_dirs(true)
+            .include_ignored(false);
+
+        let statuses = self.statuses(Some(&mut opts))?;
+        let untracked: Vec<String> = statuses
+            .iter()
+            .filter(|entry| entry.status().is_wt_new())
+            .filter_map(|entry| entry.path().map(String::from))
+            .collect();
+
+        let mut selected = Vec::new();
+        for path in paths {
+            let path_str = path.replace('\\', "/");
+            let path_str = path_str.trim_end_matches('/');
+            let dir_prefix = format!("{}/", path_str);
+            let matches: Vec<String> = untracked
+                .iter()
+                .filter(|file| file.as_str() == path_str || file.starts_with(&dir_prefix))
+                .cloned()
+                .collect();
+
+            if matches.is_empty() {
+                return Err(GitError::OperationFailed(format!(
+                    "'{}' is not an untracked file or directory",
+                    path_str
+                )));
+            }
+            selected.extend(matches);
+        }
+        selected.sort();
+        selected.dedup();
+
+        if dry_run {
+            debug!(count = selected.len(), "Dry run; nothing deleted");
+            return Ok(DiscardResult {
+                paths: selected,
+                backup_ref: None,
+                dry_run: true,
+            });
+        }
+
+        let backup_ref = backup_workdir_files(self, &selected, "cleaning untracked files")?;
+
+        let repo_root = self.workdir().unwrap_or(self.path());
+        for path in &selected {
+            fs::remove_file(repo_root.join(path))?;
+            remove_empty_parents(repo_root, Path::new(path));
+        }
+
+        info!(count = selected.len(), "Untracked files removed");
+        Ok(DiscardResult {
+            paths: selected,
+            backup_ref,
+            dry_run: false,
+        })
+    }
+
+    /// Write the files saved in a discard backup back into the working tree
+    #[instrument(skip(self))]
+    fn restore_discard_backup(&self, backup_ref: &str) -> GitResult<Vec<String>> {
+        info!("Restoring discarded files");
+        if !backup_ref.starts_with(&discard_backup_prefix()) {
+            return Err(GitError::OperationFailed(format!(
+                "'{}' is not a discard backup",
+                backup_ref
+            )));
+        }
+
+        let tree = self.find_reference(backup_ref)?.peel_to_tree()?;
+        let mut files = Vec::new();
+        tree.walk(git2::TreeWalkMode::PreOrder, |dir, entry| {
+            if entry.kind() == Some(git2::ObjectType::Blob) {
+                files.push((
+                    format!("{}{}", dir, entry.name().unwrap_or_default()),
+                    entry.id(),
+                    entry.filemode() as u32,
+                ));
+            }
+            git2::TreeWalkResult::Ok
+        })?;
+
+        let repo_root = self.workdir().unwrap_or(self.path());
+        let mut restored = Vec::new();
+        for (path, id, mode) in files {
+            let abs_path = repo_root.join(&path);
+            if let Some(parent) = abs_path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            // Replace rather than write through a symlink now at this path
+            if fs::symlink_metadata(&abs_path).is_ok_and(|m| m.file_type().is_symlink()) {
+                fs::remove_file(&abs_path)?;
+            }
+            write_backup_file(&abs_path, self.find_blob(id)?.content(), mode)?;
+            restored.push(path);
+        }
+
+        info!(count = restored.len(), "Discarded files restored");
+        Ok(restored)
+    }
+}
+
+// Helper functions
+
+/// Save the working tree contents of `paths` as a commit under the discard backup
+/// namespace. Returns `None` when none of the files exist on disk.
+pub(crate) fn backup_workdir_files(
+    repo: &Git2Repository,
+    paths: &[String],
+    reason: &str,
+) -> GitResult<Option<String>> {
+    let repo_root = repo.workdir().unwrap_or(repo.path());
+    let mut index = git2::Index::new()?;
+
+    for path in paths {
+        let abs_path = repo_root.join(path);
+        // Symlinks are saved as their target, the way git stores them
+        let (content, mode) = match fs::symlink_metadata(&abs_path) {
+            Ok(metadata) if metadata.file_type().is_symlink() => {
+                let target = fs::read_link(&abs_path)?;
+                let target = target.to_string_lossy().replace('\\', "/");
+                (target.into_bytes(), MODE_SYMLINK)
+            }
+            Ok(metadata) if metadata.is_file() => (fs::read(&abs_path)?, file_mode(&metadata)),
+            _ => continue,
+        };
+        index.add(&IndexEntry {
+            ctime: IndexTime::new(0, 0),
+            mtime: IndexTime::new(0, 0),
+            dev: 0,
+            ino: 0,
+            mode,
+            uid: 0,
+            gid: 0,
+            file_size: content.len() as u32,
+            id: repo.blob(&content)?,
+            flags: path.len().min(0xfff) as u16,
+            flags_extended: 0,
+            path: path.as_bytes().to_vec(),
+        })?;
+    }
+
+    if index.is_empty() {
+        debug!("Nothing on disk to back up");
+        return Ok(None);
+    }
+
+    let tree = repo.find_tree(index.write_tree_to(repo)?)?;
+    let signature = repo
+        .signature()
+        .or_else(|_| git2::Signature::now("Navin", "navin@localhost"))?;
+    let parent = repo.head().ok().and_then(|head| head.peel_to_commit().ok());
+    let parents: Vec<&git2::Commit> = parent.iter().collect();
+
+    let message = format!("navin: backup before {}", reason);
+    let oid = repo.commit(None, &signature, &signature, &message, &tree, &parents)?;
+
+    let short_sha: String = oid.to_string().chars().take(7).collect();
+    let refname = format!(
+        "{}/{}-{}",
+        discard_backup_prefix(),
+        Utc::now().format("%Y%m%d-%H%M%S"),
+        short_sha
+    );
+    repo.reference(&refname, oid, true, &message)?;
+
+    debug!(refname, "Discard backup recorded");
+    Ok(Some(refname))
+}
+
+fn checkout_paths<'a, S: AsRef<str>>(paths: &[S]) -> git2::build::CheckoutBuilder<'a> {
+    let mut checkout = git2::build::CheckoutBuilder::new();
+    checkout.force();
+    for path in paths {
+        checkout.path(literal_pathspec(path.as_ref()));
+    }
+    checkout
+}
+
+/// Escape pathspec wildcards so a file named `*.rs` matches only itself.
+/// git2 0.19 has no setter for `GIT_CHECKOUT_DISABLE_PATHSPEC_MATCH`.
+fn literal_pathspec(path: &str) -> String {
+    let mut escaped = String::with_capacity(path.len());
+    for c in path.chars() {
+        if matches!(c, '*' | '?' | '[' | ']' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+fn discard_backup_prefix() -> String {
+    format!("{}/{}", BACKUP_REF_PREFIX, DISCARD_BACKUP_NAMESPACE)
+}
+
+#[cfg(unix)]
+fn file_mode(metadata: &fs::Metadata) -> u32 {
+    use std::os::unix::fs::PermissionsExt;
+    if metadata.permissions().mode() & 0o111 != 0 {
+        MODE_EXECUTABLE
+    } else {
+        MODE_FILE
+    }
+}
+
+#[cfg(not(unix))]
+fn file_mode(_metadata: &fs::Metadata) -> u32 {
+    MODE_FILE
+}
+
+#[cfg(unix)]
+fn write_backup_file(path: &Path, content: &[u8], mode: u32) -> GitResult<()> {
+    use std::os::unix::fs::PermissionsExt;
+    if mode == MODE_SYMLINK {
+        if path.exists() {
+            fs::remove_file(path)?;
+        }
+        std::os::unix::fs::symlink(String::from_utf8_lossy(content).as_ref(), path)?;
+        return Ok(());
+    }
+    fs::write(path, content)?;
+    let permissions = if mode == MODE_EXECUTABLE {
+        0o755
+    } else {
+        0o644
+    };
+    fs::set_permissions(path, fs::Permissions::from_mode(permissions))?;
+    Ok(())
+}
+
+/// Without symlink support a link is written as a file holding its target,
+/// as git does with `core.symlinks` off
+#[cfg(not(unix))]
+fn write_backup_file(path: &Path, content: &[u8], _mode: u32) -> GitResult<()> {
+    fs::write(path, content)?;
+    Ok(())
+}
+
+fn remove_empty_parents(repo_root: &Path, path: &Path) {
+    let mut dir = path.parent();
+    while let Some(current) = dir {
+        if current.as_os_str().is_empty() {
+            break;
+        }
+        // remove_dir fails on non-empty directories, which ends the walk
+        if fs::remove_dir(repo_root.join(current)).is_err() {
+            break;
+        }
+        dir = current.parent();
+    }
+}
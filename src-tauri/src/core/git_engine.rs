@@ -1,6 +1,9 @@
 use crate::core::git_error::{GitError, GitResult};
 use crate::models::git_repository::*;
-use git2::{Repository as Git2Repository, StatusOptions};
+use git2::{
+    Delta, Diff, DiffFindOptions, DiffOptions, Patch, Repository as Git2Repository,
+    SubmoduleIgnore, SubmoduleStatus as Git2SubmoduleStatus,
+};
 use std::path::{Path, PathBuf};
 use tracing::{debug, error, instrument};
 
@@ -59,6 +62,7 @@ impl GitEngine {
             .collect();
 
         let info = RepositoryInfo {
+            id: None,
             path: self.repo_path.to_string_lossy().to_string(),
             name,
             current_branch: current_branch.clone(),
@@ -88,94 +92,113 @@ impl GitEngine {
         Ok(head.shorthand().map(String::from))
     }
 
+    /// Staged changes (with renames and copies), unstaged changes (with renames
+    /// to untracked files), line counts, conflicts and submodule state.
     #[instrument(skip(self), fields(repo_path = %self.repo_path.display()))]
-    pub fn get_status(&self) -> GitResult<RepositoryStatus> {
-        let mut opts = StatusOptions::new();
-        opts.include_untracked(true);
-        opts.recurse_untracked_dirs(true);
+    pub fn get_status(&self, include_ignored: bool) -> GitResult<RepositoryStatus> {
+        let index = self.repo.index()?;
 
-        let statuses = self.repo.statuses(Some(&mut opts))?;
-
-        let mut staged = Vec::new();
-        let mut unstaged = Vec::new();
-        let mut untracked = Vec::new();
         let mut conflicted = Vec::new();
-
-        for entry in statuses.iter() {
-            let status = entry.status();
-            let path = entry.path().unwrap_or("unknown").to_string();
-
-            if status.is_conflicted() {
-                conflicted.push(FileStatus {
-                    path: path.clone(),
-                    status: FileStatusType::Conflicted,
-                    additions: None,
-                    deletions: None,
-                });
+        if index.has_conflicts() {
+            for conflict in index.conflicts()? {
+                let conflict = conflict?;
+                let entry = conflict.our.or(conflict.their).or(conflict.ancestor);
+                if let Some(entry) = entry {
+                    conflicted.push(FileStatus {
+                        path: String::from_utf8_lossy(&entry.path).to_string(),
+                        status: FileStatusType::Conflicted,
+                        additions: None,
+                        deletions: None,
+                    });
+                }
             }
+        }
 
-            if status.is_index_new()
-                || status.is_index_modified()
-                || status.is_index_deleted()
-                || status.is_index_renamed()
-            {
-                let file_status = if status.is_index_new() {
-                    FileStatusType::Added
-                } else if status.is_index_modified() {
-                    FileStatusType::Modified
-                } else if status.is_index_deleted() {
-                    FileStatusType::Deleted
-                } else {
-                    FileStatusType::Modified
-                };
-
-                staged.push(FileStatus {
-                    path: path.clone(),
-                    status: file_status,
-                    additions: None,
-                    deletions: None,
-                });
-            }
+        // An unborn HEAD compares the index with an empty tree
+        let head_tree = match self.repo.head() {
+            Ok(head) => Some(head.peel_to_tree()?),
+            Err(_) => None,
+        };
+        let mut staged_diff =
+            self.repo
+                .diff_tree_to_index(head_tree.as_ref(), Some(&index), None)?;
+        staged_diff.find_similar(Some(DiffFindOptions::new().renames(true).copies(true)))?;
+
+        let mut workdir_options = DiffOptions::new();
+        workdir_options
+            .include_untracked(true)
+            .recurse_untracked_dirs(true)
+            .include_ignored(include_ignored)
+            .include_typechange(true);
+        let mut workdir_diff = self
+            .repo
+            .diff_index_to_workdir(Some(&index), Some(&mut workdir_options))?;
+        workdir_diff.find_similar(Some(
+            DiffFindOptions::new().renames(true).for_untracked(true),
+        ))?;
 
-            if status.is_wt_new() {
-                untracked.push(FileStatus {
-                    path: path.clone(),
-                    status: FileStatusType::Untracked,
-                    additions: None,
-                    deletions: None,
-                });
-            } else if status.is_wt_modified() || status.is_wt_deleted() || status.is_wt_renamed() {
-                let file_status = if status.is_wt_modified() {
-                    FileStatusType::Modified
-                } else if status.is_wt_deleted() {
-                    FileStatusType::Deleted
-                } else {
-                    FileStatusType::Modified
-                };
-
-                unstaged.push(FileStatus {
-                    path: path.clone(),
-                    status: file_status,
-                    additions: None,
-                    deletions: None,
-                });
+        let staged = diff_file_statuses(&staged_diff)?;
+        let mut unstaged = Vec::new();
+        let mut untracked = Vec::new();
+        let mut ignored = Vec::new();
+        for file in diff_file_statuses(&workdir_diff)? {
+            match file.status {
+                FileStatusType::Untracked => untracked.push(file),
+                FileStatusType::Ignored => ignored.push(file),
+                _ => unstaged.push(file),
             }
         }
 
+        let submodules = self.submodule_statuses()?;
+
+        // A submodule with local changes or a moved HEAD is a change, as in `git status`
         let is_clean = staged.is_empty()
             && unstaged.is_empty()
             && untracked.is_empty()
-            && conflicted.is_empty();
+            && conflicted.is_empty()
+            && submodules.iter().all(|s| !s.dirty && !s.out_of_date);
 
+        debug!(
+            staged = staged.len(),
+            unstaged = unstaged.len(),
+            untracked = untracked.len(),
+            "Repository status retrieved"
+        );
         Ok(RepositoryStatus {
             staged,
             unstaged,
             untracked,
             conflicted,
+            ignored,
+            submodules,
             is_clean,
         })
     }
 
+    fn submodule_statuses(&self) -> GitResult<Vec<SubmoduleStatus>> {
+        let mut result = Vec::new();
+        for submodule in self.repo.submodules()? {
+            let name = submodule.name().unwrap_or("").to_string();
+            let flags = self.repo.submodule_status(&name, SubmoduleIgnore::None)?;
+
+            result.push(SubmoduleStatus {
+                path: submodule.path().to_string_lossy().replace('\\', "/"),
+                url: submodule.url().map(String::from),
+                recorded_sha: submodule.index_id().map(|oid| oid.to_string()),
+                checked_out_sha: submodule.workdir_id().map(|oid| oid.to_string()),
+                initialized: !flags.contains(Git2SubmoduleStatus::WD_UNINITIALIZED),
+                out_of_date: flags.contains(Git2SubmoduleStatus::WD_MODIFIED),
+                dirty: flags.intersects(
+                    Git2SubmoduleStatus::WD_INDEX_MODIFIED
+                        | Git2SubmoduleStatus::WD_WD_MODIFIED
+                        | Git2SubmoduleStatus::WD_UNTRACKED,
+                ),
+                name,
+            });
+        }
+        Ok(result)
+    }
+
     #[instrument(skip(self))]
     pub fn get_config(&self) -> GitResult<(String, String)> {
         debug!("Fetching git configuration");
@@ -231,3 +254,60 @@ impl GitEngine {
         Ok(())
     }
 }
+
+// Helper functions
+
+/// Turn the deltas of a status diff into file statuses with line counts.
+/// Conflicts are skipped since they are listed from the index.
+fn diff_file_statuses(diff: &Diff) -> GitResult<Vec<FileStatus>> {
+    let mut files = Vec::new();
+    for (index, delta) in diff.deltas().enumerate() {
+        let path = delta
+            .new_file()
+            .path()
+            .or_else(|| delta.old_file().path())
+            .map(|p| p.to_string_lossy().replace('\\', "/"))
+            .unwrap_or_else(|| "unknown".to_string());
+
+        let status = match delta.status() {
+            Delta::Added => FileStatusType::Added,
+            Delta::Deleted => FileStatusType::Deleted,
+            Delta::Renamed => FileStatusType::Renamed {
+                old_path: delta
+                    .old_file()
+                    .path()
+                    .map(|p| p.to_string_lossy().replace('\\', "/"))
+                    .unwrap_or_default(),
+            },
+            Delta::Copied => FileStatusType::Copied,
+            Delta::Untracked => FileStatusType::Untracked,
+            Delta::Ignored => FileStatusType::Ignored,
+            Delta::Conflicted | Delta::Unmodified => continue,
+            _ => FileStatusType::Modified,
+        };
+
+        // Untracked and ignored files, binaries and submodules have no line counts
+        let counted = !matches!(delta.status(), Delta::Untracked | Delta::Ignored)
+            && delta.new_file().mode() != git2::FileMode::Commit;
+        let patch = if counted {
+            Patch::from_diff(diff, index)?
+        } else {
+            None
+        };
+        let (additions, deletions) = match patch {
+            Some(patch) if !patch.delta().flags().is_binary() => {
+                let (_, additions, deletions) = patch.line_stats()?;
+                (Some(additions), Some(deletions))
+            }
+            _ => (None, None),
+        };
+
+        files.push(FileStatus {
+            path,
+            status,
+            additions,
+            deletions,
+        });
+    }
+    Ok(files)
+}
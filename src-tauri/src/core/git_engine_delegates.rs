@@ -1,12 +1,23 @@
+use crate::core::git_blame_operations::GitBlameOperations;
 use crate::core::git_branch_ops::GitBranchOps;
+use crate::core::git_conflict_operations::GitConflictOperations;
 use crate::core::git_diff_operations::GitDiffOperations;
+use crate::core::git_discard_operations::GitDiscardOperations;
 use crate::core::git_engine::GitEngine;
 use crate::core::git_error::GitResult;
 use crate::core::git_history_operations::GitHistoryOperations;
+use crate::core::git_hunk_operations::GitHunkOperations;
+use crate::core::git_merge_operations::GitMergeOperations;
 use crate::core::git_operations::GitOperations;
+use crate::core::git_progress::OperationProgress;
+use crate::core::git_rebase_operations::GitRebaseOperations;
+use crate::core::git_reflog_operations::GitReflogOperations;
 use crate::core::git_remote_operations::GitRemoteOperations;
+use crate::core::git_reset_operations::GitResetOperations;
+use crate::core::git_tag_operations::GitTagOperations;
+use crate::core::git_worktree_operations::GitWorktreeOperations;
 use crate::models::git_repository::*;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 /// Delegate methods for GitEngine - Phase 1 operations
 impl GitEngine {
@@ -27,6 +38,49 @@ impl GitEngine {
         self.repo.unstage_all()
     }
 
+    pub fn stage_hunk(
+        &self,
+        file_path: &str,
+        hunk: &DiffHunk,
+        selected_lines: Option<&[usize]>,
+    ) -> GitResult<()> {
+        self.repo.stage_hunk(file_path, hunk, selected_lines)
+    }
+
+    pub fn unstage_hunk(
+        &self,
+        file_path: &str,
+        hunk: &DiffHunk,
+        selected_lines: Option<&[usize]>,
+    ) -> GitResult<()> {
+        self.repo.unstage_hunk(file_path, hunk, selected_lines)
+    }
+
+    pub fn discard_hunk(
+        &self,
+        file_path: &str,
+        hunk: &DiffHunk,
+        selected_lines: Option<&[usize]>,
+    ) -> GitResult<DiscardResult> {
+        self.repo.discard_hunk(file_path, hunk, selected_lines)
+    }
+
+    pub fn discard_changes(
+        &self,
+        paths: &[String],
+        source: RestoreSource,
+    ) -> GitResult<DiscardResult> {
+        self.repo.discard_changes(paths, source)
+    }
+
+    pub fn clean_untracked(&self, paths: &[String], dry_run: bool) -> GitResult<DiscardResult> {
+        self.repo.clean_untracked(paths, dry_run)
+    }
+
+    pub fn restore_discard_backup(&self, backup_ref: &str) -> GitResult<Vec<String>> {
+        self.repo.restore_discard_backup(backup_ref)
+    }
+
     pub fn create_commit(
         &self,
         message: &str,
@@ -36,6 +90,10 @@ impl GitEngine {
         self.repo.create_commit(message, author_name, author_email)
     }
 
+    pub fn reset_to(&mut self, target: &str, mode: ResetMode) -> GitResult<ResetResult> {
+        self.repo.reset_to(target, mode)
+    }
+
     // Branch operations
     pub fn get_branches(&self) -> GitResult<Vec<Branch>> {
         self.repo.get_branches()
@@ -53,9 +111,60 @@ impl GitEngine {
         self.repo.delete_branch(name, force)
     }
 
+    // Merge operations
+    pub fn merge_branch(
+        &self,
+        source: &str,
+        mode: MergeMode,
+        strategy: Option<MergeStrategy>,
+        message: Option<&str>,
+    ) -> GitResult<MergeResult> {
+        self.repo.merge_branch(source, mode, strategy, message)
+    }
+
+    // Conflict resolution operations
+    pub fn get_conflict_versions(&self, file_path: &str) -> GitResult<ConflictVersions> {
+        self.repo.get_conflict_versions(file_path)
+    }
+
+    pub fn resolve_conflict(&self, resolution: &ConflictResolution) -> GitResult<()> {
+        self.repo.resolve_conflict(resolution)
+    }
+
+    pub fn abort_merge(&mut self) -> GitResult<ResetResult> {
+        self.repo.abort_merge()
+    }
+
+    pub fn continue_merge(&self, message: Option<&str>) -> GitResult<String> {
+        self.repo.continue_merge(message)
+    }
+
     // Phase 2: History operations
-    pub fn get_commits(&self, limit: usize, offset: usize) -> GitResult<Vec<CommitSummary>> {
-        self.repo.get_commits(limit, offset)
+    pub fn get_commits(
+        &self,
+        limit: usize,
+        offset: usize,
+        range: &HistoryRange,
+    ) -> GitResult<Vec<CommitSummary>> {
+        self.repo.get_commits(limit, offset, range)
+    }
+
+    pub fn get_commit_graph(
+        &self,
+        limit: usize,
+        offset: usize,
+        state: Option<&GraphLayoutState>,
+    ) -> GitResult<CommitGraphPage> {
+        self.repo.get_commit_graph(limit, offset, state)
+    }
+
+    pub fn search_commits(
+        &self,
+        query: &CommitSearchQuery,
+        limit: usize,
+        offset: usize,
+    ) -> GitResult<Vec<CommitSummary>> {
+        self.repo.search_commits(query, limit, offset)
     }
 
     pub fn get_commit_details(&self, sha: &str) -> GitResult<Commit> {
@@ -66,25 +175,117 @@ impl GitEngine {
         self.repo.get_commit_diff(sha)
     }
 
-    pub fn get_file_history(&self, file_path: &str, limit: usize) -> GitResult<Vec<CommitSummary>> {
-        self.repo.get_file_history(file_path, limit)
+    pub fn get_file_history(
+        &self,
+        file_path: &str,
+        limit: usize,
+        range: &HistoryRange,
+    ) -> GitResult<Vec<FileHistoryEntry>> {
+        self.repo.get_file_history(file_path, limit, range)
+    }
+
+    pub fn cherry_pick_commits(
+        &self,
+        shas: &[String],
+        mainline: Option<u32>,
+    ) -> GitResult<MergeResult> {
+        self.repo.cherry_pick_commits(shas, mainline)
+    }
+
+    pub fn revert_commits(&self, shas: &[String], mainline: Option<u32>) -> GitResult<MergeResult> {
+        self.repo.revert_commits(shas, mainline)
+    }
+
+    pub fn get_file_blame(
+        &self,
+        file_path: &str,
+        revision: Option<&str>,
+        ignore_whitespace: bool,
+        follow_moves: bool,
+    ) -> GitResult<BlameInfo> {
+        self.repo
+            .get_file_blame(file_path, revision, ignore_whitespace, follow_moves)
+    }
+
+    // Rebase operations
+    pub fn get_rebase_todo(&self, onto: &str) -> GitResult<Vec<CommitSummary>> {
+        self.repo.get_rebase_todo(onto)
+    }
+
+    pub fn start_interactive_rebase(
+        &self,
+        onto: &str,
+        plan: Vec<RebasePlanStep>,
+    ) -> GitResult<RebaseStatus> {
+        self.repo.start_interactive_rebase(onto, plan)
+    }
+
+    pub fn continue_rebase(&self) -> GitResult<RebaseStatus> {
+        self.repo.continue_rebase()
+    }
+
+    pub fn skip_rebase_step(&self) -> GitResult<RebaseStatus> {
+        self.repo.skip_rebase_step()
+    }
+
+    pub fn abort_rebase(&self) -> GitResult<()> {
+        self.repo.abort_rebase()
+    }
+
+    pub fn get_rebase_status(&self) -> GitResult<RebaseStatus> {
+        self.repo.get_rebase_status()
+    }
+
+    // Reflog operations
+    pub fn get_reflog(&self, reference: Option<&str>) -> GitResult<Vec<ReflogEntry>> {
+        self.repo.get_reflog(reference)
+    }
+
+    pub fn restore_reflog_entry(
+        &mut self,
+        reference: Option<&str>,
+        index: usize,
+        stash_changes: bool,
+    ) -> GitResult<String> {
+        self.repo
+            .restore_reflog_entry(reference, index, stash_changes)
     }
 
     // Phase 2: Diff operations
-    pub fn get_file_diff_unstaged<P: AsRef<Path>>(&self, path: P) -> GitResult<FileDiff> {
-        self.repo.get_file_diff_unstaged(path)
+    pub fn get_file_diff_unstaged<P: AsRef<Path>>(
+        &self,
+        path: P,
+        options: &DiffRequestOptions,
+    ) -> GitResult<FileDiff> {
+        self.repo.get_file_diff_unstaged(path, options)
     }
 
-    pub fn get_file_diff_staged<P: AsRef<Path>>(&self, path: P) -> GitResult<FileDiff> {
-        self.repo.get_file_diff_staged(path)
+    pub fn get_file_diff_staged<P: AsRef<Path>>(
+        &self,
+        path: P,
+        options: &DiffRequestOptions,
+    ) -> GitResult<FileDiff> {
+        self.repo.get_file_diff_staged(path, options)
     }
 
     pub fn get_diff_between_commits(
         &self,
         commit1: &str,
         commit2: &str,
+        options: &DiffRequestOptions,
     ) -> GitResult<Vec<FileDiff>> {
-        self.repo.get_diff_between_commits(commit1, commit2)
+        self.repo
+            .get_diff_between_commits(commit1, commit2, options)
+    }
+
+    pub fn get_commit_file_diff(
+        &self,
+        from: Option<&str>,
+        to: &str,
+        file_path: &str,
+        options: &DiffRequestOptions,
+    ) -> GitResult<FileDiff> {
+        self.repo.get_commit_file_diff(from, to, file_path, options)
     }
 
     // Phase 2: Remote operations
@@ -100,15 +301,87 @@ impl GitEngine {
         self.repo.remove_remote(name)
     }
 
-    pub fn fetch(&self, remote_name: &str) -> GitResult<()> {
-        self.repo.fetch(remote_name)
+    pub fn fetch(&self, remote_name: &str, progress: Option<&OperationProgress>) -> GitResult<()> {
+        self.repo.fetch(remote_name, progress)
+    }
+
+    pub fn push(
+        &self,
+        remote_name: &str,
+        branch: &str,
+        force: bool,
+        progress: Option<&OperationProgress>,
+    ) -> GitResult<()> {
+        self.repo.push(remote_name, branch, force, progress)
+    }
+
+    pub fn pull(
+        &self,
+        remote_name: &str,
+        branch: &str,
+        strategy: PullStrategy,
+        progress: Option<&OperationProgress>,
+    ) -> GitResult<MergeResult> {
+        self.repo.pull(remote_name, branch, strategy, progress)
+    }
+
+    pub fn integrate_upstream(
+        &self,
+        remote_name: &str,
+        branch: &str,
+        strategy: PullStrategy,
+    ) -> GitResult<MergeResult> {
+        self.repo.integrate_upstream(remote_name, branch, strategy)
+    }
+
+    // Tag operations
+    pub fn list_tags(&self) -> GitResult<Vec<Tag>> {
+        self.repo.list_tags()
+    }
+
+    pub fn create_lightweight_tag(&self, name: &str, target: Option<&str>) -> GitResult<String> {
+        self.repo.create_lightweight_tag(name, target)
+    }
+
+    pub fn create_annotated_tag(
+        &self,
+        name: &str,
+        target: Option<&str>,
+        message: &str,
+    ) -> GitResult<String> {
+        self.repo.create_annotated_tag(name, target, message)
+    }
+
+    pub fn delete_tag(&self, name: &str) -> GitResult<()> {
+        self.repo.delete_tag(name)
+    }
+
+    pub fn push_tag(&self, remote_name: &str, name: &str, force: bool) -> GitResult<()> {
+        self.repo.push_tag(remote_name, name, force)
+    }
+
+    // Worktree operations
+    pub fn list_worktrees(&self) -> GitResult<Vec<Worktree>> {
+        self.repo.list_worktrees()
+    }
+
+    pub fn add_worktree(&self, options: &AddWorktreeOptions) -> GitResult<Worktree> {
+        self.repo.add_worktree(options)
+    }
+
+    pub fn lock_worktree(&self, name: &str, reason: Option<&str>) -> GitResult<()> {
+        self.repo.lock_worktree(name, reason)
+    }
+
+    pub fn unlock_worktree(&self, name: &str) -> GitResult<()> {
+        self.repo.unlock_worktree(name)
     }
 
-    pub fn push(&self, remote_name: &str, branch: &str, force: bool) -> GitResult<()> {
-        self.repo.push(remote_name, branch, force)
+    pub fn prune_worktrees(&self) -> GitResult<Vec<String>> {
+        self.repo.prune_worktrees()
     }
 
-    pub fn pull(&self, remote_name: &str, branch: &str) -> GitResult<()> {
-        self.repo.pull(remote_name, branch)
+    pub fn worktree_path(&self, name: &str) -> GitResult<PathBuf> {
+        self.repo.worktree_path(name)
     }
 }
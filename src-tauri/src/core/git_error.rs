@@ -25,6 +25,9 @@ pub enum GitError {
     #[error("Cannot checkout branch: uncommitted changes in {0}")]
     UncommittedChanges(String),
 
+    #[error("Working tree has uncommitted changes in {0}")]
+    DirtyWorkingTree(String),
+
     #[error("No staged changes to commit")]
     NoStagedChanges,
 
@@ -34,6 +37,15 @@ pub enum GitError {
     #[error("Merge conflict in {0}")]
     MergeConflict(String),
 
+    #[error("Authentication failed for {0}")]
+    AuthenticationFailed(String),
+
+    #[error("Credential store error: {0}")]
+    CredentialStore(String),
+
+    #[error("Operation was cancelled")]
+    Cancelled,
+
     #[error("Git2 library error: {0}")]
     #[serde(serialize_with = "serialize_git2_error")]
     Git2Error(#[from] git2::Error),
@@ -58,3 +70,16 @@ where
 }
 
 pub type GitResult<T> = Result<T, GitError>;
+
+/// Tracked files with staged or unstaged changes, as listed in `DirtyWorkingTree`
+pub(crate) fn dirty_paths(repo: &git2::Repository) -> GitResult<Vec<String>> {
+    let mut opts = git2::StatusOptions::new();
+    opts.include_untracked(false);
+
+    let statuses = repo.statuses(Some(&mut opts))?;
+    Ok(statuses
+        .iter()
+        .filter(|entry| entry.status() != git2::Status::CURRENT)
+        .filter_map(|entry| entry.path().map(String::from))
+        .collect())
+}
@@ -0,0 +1,175 @@
+use crate::models::git_repository::{GraphEdge, GraphLayoutState};
+
+/// Assigns commits to columns ("lanes") of the history graph.
+///
+/// Commits must be fed child-before-parent (topological order). Each lane holds
+/// the commit it is waiting for; a commit takes the lane that waits for it and
+/// hands it on to its first parent. A parent that another lane already waits for
+/// is joined with an edge instead of opening a second lane, so every commit has
+/// exactly one lane when its row is reached.
+pub struct GraphLayout {
+    lanes: Vec<Option<String>>,
+}
+
+impl GraphLayout {
+    pub fn new() -> Self {
+        Self { lanes: Vec::new() }
+    }
+
+    /// Continue a layout from the state returned with a previous page
+    pub fn from_state(state: &GraphLayoutState) -> Self {
+        Self {
+            lanes: state.lanes.clone(),
+        }
+    }
+
+    pub fn state(&self, offset: usize) -> GraphLayoutState {
+        GraphLayoutState {
+            offset,
+            lanes: self.lanes.clone(),
+        }
+    }
+
+    /// Place a commit, returning its column and the edges from its row to the next
+    pub fn place(&mut self, sha: &str, parents: &[String]) -> (usize, Vec<GraphEdge>) {
+        let column = match self
+            .lanes
+            .iter()
+            .position(|lane| lane.as_deref() == Some(sha))
+        {
+            Some(column) => column,
+            // Branch tips start in the first free lane
+            None => self.free_lane(),
+        };
+        self.lanes[column] = None;
+
+        let mut edges = Vec::new();
+        let mut parent_lanes = Vec::new();
+        for (index, parent) in parents.iter().enumerate() {
+            let is_merge = index > 0;
+            let to_column = match self
+                .lanes
+                .iter()
+                .position(|lane| lane.as_deref() == Some(parent))
+            {
+                Some(existing) => existing,
+                None => {
+                    let lane = if index == 0 { column } else { self.free_lane() };
+                    self.lanes[lane] = Some(parent.clone());
+                    lane
+                }
+            };
+            parent_lanes.push(to_column);
+            edges.push(GraphEdge {
+                from_column: column,
+                to_column,
+                is_merge,
+            });
+        }
+
+        // Lanes waiting for other commits pass straight through this row
+        for (lane, waiting) in self.lanes.iter().enumerate() {
+            if waiting.is_some() && !parent_lanes.contains(&lane) {
+                edges.push(GraphEdge {
+                    from_column: lane,
+                    to_column: lane,
+                    is_merge: false,
+                });
+            }
+        }
+
+        while matches!(self.lanes.last(), Some(None)) {
+            self.lanes.pop();
+        }
+
+        (column, edges)
+    }
+
+    fn free_lane(&mut self) -> usize {
+        match self.lanes.iter().position(Option::is_none) {
+            Some(lane) => lane,
+            None => {
+                self.lanes.push(None);
+                self.lanes.len() - 1
+            }
+        }
+    }
+}
+
+impl Default for GraphLayout {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Place a commit and flatten its edges to (from, to, is_merge)
+    fn place(
+        layout: &mut GraphLayout,
+        sha: &str,
+        parents: &[&str],
+    ) -> (usize, Vec<(usize, usize, bool)>) {
+        let parents: Vec<String> = parents.iter().map(|p| p.to_string()).collect();
+        let (column, edges) = layout.place(sha, &parents);
+        let edges = edges
+            .iter()
+            .map(|edge| (edge.from_column, edge.to_column, edge.is_merge))
+            .collect();
+        (column, edges)
+    }
+
+    #[test]
+    fn merge_opens_a_lane_for_its_second_parent_and_joins_it_back() {
+        // m merges b into a; both branch off c
+        let mut layout = GraphLayout::new();
+        assert_eq!(
+            place(&mut layout, "m", &["a", "b"]),
+            (0, vec![(0, 0, false), (0, 1, true)])
+        );
+        assert_eq!(
+            place(&mut layout, "a", &["c"]),
+            (0, vec![(0, 0, false), (1, 1, false)])
+        );
+        assert_eq!(place(&mut layout, "b", &["c"]), (1, vec![(1, 0, false)]));
+        assert_eq!(place(&mut layout, "c", &[]), (0, vec![]));
+        assert!(layout.lanes.is_empty());
+    }
+
+    #[test]
+    fn branch_tips_take_the_first_free_lane() {
+        let mut layout = GraphLayout::new();
+        assert_eq!(place(&mut layout, "x", &["p"]), (0, vec![(0, 0, false)]));
+        // y is not awaited by any lane and parent p already has one
+        assert_eq!(place(&mut layout, "y", &["p"]), (1, vec![(1, 0, false)]));
+        assert_eq!(layout.lanes, vec![Some("p".to_string())]);
+
+        // A lane freed by a root commit is reused before a new one is added
+        let mut layout = GraphLayout::new();
+        place(&mut layout, "m", &["a", "b"]);
+        place(&mut layout, "a", &["c"]);
+        // t opens a third lane; its own edge is listed before the pass-throughs
+        assert_eq!(
+            place(&mut layout, "t", &["u"]),
+            (2, vec![(2, 2, false), (0, 0, false), (1, 1, false)])
+        );
+        place(&mut layout, "c", &[]);
+        assert_eq!(place(&mut layout, "s", &["v"]).0, 0);
+    }
+
+    #[test]
+    fn layout_continues_from_saved_state() {
+        let mut whole = GraphLayout::new();
+        place(&mut whole, "m", &["a", "b"]);
+        let mut resumed = GraphLayout::from_state(&whole.state(1));
+
+        for (sha, parents) in [("a", &["c"][..]), ("b", &["c"]), ("c", &[])] {
+            assert_eq!(
+                place(&mut resumed, sha, parents),
+                place(&mut whole, sha, parents)
+            );
+        }
+    }
+}
@@ -1,26 +1,71 @@
-use crate::core::git_error::GitResult;
-use crate::models::git_repository::{Commit, CommitSummary, FileDiff};
+use crate::core::git_error::{GitError, GitResult};
+use crate::core::git_graph_layout::GraphLayout;
+use crate::core::git_merge_operations::{conflicted_paths, ensure_clean_index};
+use crate::models::git_repository::{
+    Commit, CommitGraphPage, CommitGraphRow, CommitSearchQuery, CommitSummary, FileDiff,
+    FileHistoryEntry, GraphLayoutState, HistoryOrder, HistoryRange, MergeResult,
+};
 use chrono::{TimeZone, Utc};
-use git2::{Commit as Git2Commit, Repository as Git2Repository};
-use tracing::{debug, info, instrument};
+use git2::{
+    Commit as Git2Commit, Delta, Diff, DiffFindOptions, DiffOptions, Patch,
+    Repository as Git2Repository, RevparseMode, Revwalk, Signature, Sort, Tree,
+};
+use regex::Regex;
+use std::path::Path;
+use tracing::{debug, info, instrument, warn};
 
 use super::git_diff_helpers::diff_to_file_diffs;
 
 /// Extension trait for GitEngine to handle commit history operations
 pub trait GitHistoryOperations {
-    fn get_commits(&self, limit: usize, offset: usize) -> GitResult<Vec<CommitSummary>>;
+    fn get_commits(
+        &self,
+        limit: usize,
+        offset: usize,
+        range: &HistoryRange,
+    ) -> GitResult<Vec<CommitSummary>>;
+    fn get_commit_graph(
+        &self,
+        limit: usize,
+        offset: usize,
+        state: Option<&GraphLayoutState>,
+    ) -> GitResult<CommitGraphPage>;
+    fn search_commits(
+        &self,
+        query: &CommitSearchQuery,
+        limit: usize,
+        offset: usize,
+    ) -> GitResult<Vec<CommitSummary>>;
     fn get_commit_details(&self, sha: &str) -> GitResult<Commit>;
     fn get_commit_diff(&self, sha: &str) -> GitResult<Vec<FileDiff>>;
-    fn get_file_history(&self, file_path: &str, limit: usize) -> GitResult<Vec<CommitSummary>>;
+    fn get_file_history(
+        &self,
+        file_path: &str,
+        limit: usize,
+        range: &HistoryRange,
+    ) -> GitResult<Vec<FileHistoryEntry>>;
+    fn cherry_pick_commits(&self, shas: &[String], mainline: Option<u32>)
+        -> GitResult<MergeResult>;
+    fn revert_commits(&self, shas: &[String], mainline: Option<u32>) -> GitResult<MergeResult>;
+}
+
+/// Which way a commit is applied on top of HEAD
+#[derive(Clone, Copy)]
+enum ApplyKind {
+    CherryPick,
+    Revert,
 }
 
 impl GitHistoryOperations for Git2Repository {
     #[instrument(skip(self))]
-    fn get_commits(&self, limit: usize, offset: usize) -> GitResult<Vec<CommitSummary>> {
+    fn get_commits(
+        &self,
+        limit: usize,
+        offset: usize,
+        range: &HistoryRange,
+    ) -> GitResult<Vec<CommitSummary>> {
         info!(limit, offset, "Getting paginated commits");
-        let mut revwalk = self.revwalk()?;
-        revwalk.push_head()?;
-        revwalk.set_sorting(git2::Sort::TIME)?;
+        let revwalk = history_revwalk(self, range)?;
 
         let commits: Vec<CommitSummary> = revwalk
             .skip(offset)
@@ -36,6 +81,95 @@ impl GitHistoryOperations for Git2Repository {
         Ok(commits)
     }
 
+    /// Lay out a page of history as a graph.
+    ///
+    /// Pass the state returned with the previous page to continue its lanes; when it
+    /// is missing or was saved at another offset, the commits before `offset` are
+    /// laid out again so the columns match what earlier pages showed.
+    #[instrument(skip(self, state))]
+    fn get_commit_graph(
+        &self,
+        limit: usize,
+        offset: usize,
+        state: Option<&GraphLayoutState>,
+    ) -> GitResult<CommitGraphPage> {
+        info!(limit, offset, "Getting commit graph");
+        let mut revwalk = self.revwalk()?;
+        revwalk.push_head()?;
+        // Parents must come after all of their children for the lanes to line up
+        revwalk.set_sorting(git2::Sort::TOPOLOGICAL | git2::Sort::TIME)?;
+
+        let (mut layout, skip) = match state {
+            Some(state) if state.offset == offset => (GraphLayout::from_state(state), offset),
+            _ => (GraphLayout::new(), 0),
+        };
+
+        let mut rows = Vec::new();
+        for (index, oid) in revwalk.enumerate().skip(skip) {
+            if index >= offset + limit {
+                break;
+            }
+            let commit = self.find_commit(oid?)?;
+            let summary = commit_to_summary(&commit)?;
+            let (column, edges) = layout.place(&summary.sha, &summary.parents);
+            if index >= offset {
+                rows.push(CommitGraphRow {
+                    commit: summary,
+                    column,
+                    edges,
+                });
+            }
+        }
+
+        debug!(count = rows.len(), "Commit graph retrieved");
+        Ok(CommitGraphPage {
+            state: layout.state(offset + rows.len()),
+            rows,
+        })
+    }
+
+    /// Find commits matching every filter of the query, newest first.
+    ///
+    /// Path and content filters compare a merge commit with its first parent only.
+    #[instrument(skip(self))]
+    fn search_commits(
+        &self,
+        query: &CommitSearchQuery,
+        limit: usize,
+        offset: usize,
+    ) -> GitResult<Vec<CommitSummary>> {
+        info!(limit, offset, "Searching commits");
+        let message_pattern = query
+            .message
+            .as_deref()
+            .map(Regex::new)
+            .transpose()
+            .map_err(|e| GitError::OperationFailed(format!("Invalid message pattern: {}", e)))?;
+
+        let mut revwalk = self.revwalk()?;
+        revwalk.push_head()?;
+        revwalk.set_sorting(git2::Sort::TIME)?;
+
+        let mut commits = Vec::new();
+        let mut matched = 0;
+        for oid in revwalk {
+            if commits.len() >= limit {
+                break;
+            }
+            let commit = self.find_commit(oid?)?;
+            if !commit_matches(self, &commit, query, message_pattern.as_ref())? {
+                continue;
+            }
+            matched += 1;
+            if matched > offset {
+                commits.push(commit_to_summary(&commit)?);
+            }
+        }
+
+        debug!(count = commits.len(), "Commit search finished");
+        Ok(commits)
+    }
+
     #[instrument(skip(self))]
     fn get_commit_details(&self, sha: &str) -> GitResult<Commit> {
         info!(sha, "Getting commit details");
@@ -67,39 +201,141 @@ impl GitHistoryOperations for Git2Repository {
         Ok(file_diffs)
     }
 
+    /// List the commits that changed a file, following it back through renames.
+    ///
+    /// Commits are skipped by comparing the file's blob id with the parents' before
+    /// any diff is computed, and a merge that kept one parent's version is not listed.
     #[instrument(skip(self))]
-    fn get_file_history(&self, file_path: &str, limit: usize) -> GitResult<Vec<CommitSummary>> {
+    fn get_file_history(
+        &self,
+        file_path: &str,
+        limit: usize,
+        range: &HistoryRange,
+    ) -> GitResult<Vec<FileHistoryEntry>> {
         info!(file_path, limit, "Getting file history");
-        let mut revwalk = self.revwalk()?;
-        revwalk.push_head()?;
-        revwalk.set_sorting(git2::Sort::TIME)?;
+        let revwalk = history_revwalk(self, range)?;
 
-        let mut commits = Vec::new();
+        let mut path = file_path.replace('\\', "/");
+        let mut entries = Vec::new();
         for oid in revwalk {
-            if commits.len() >= limit {
+            if entries.len() >= limit {
                 break;
             }
 
-            if let Ok(oid) = oid {
-                if let Ok(commit) = self.find_commit(oid) {
-                    // Check if this commit touched the file
-                    if commit_touches_file(self, &commit, file_path)? {
-                        if let Ok(summary) = commit_to_summary(&commit) {
-                            commits.push(summary);
-                        }
-                    }
-                }
+            let commit = self.find_commit(oid?)?;
+            let Some(change) = file_change(self, &commit, &path)? else {
+                continue;
+            };
+
+            let entry = FileHistoryEntry {
+                commit: commit_to_summary(&commit)?,
+                path: path.clone(),
+                old_path: change.old_path,
+                additions: change.additions,
+                deletions: change.deletions,
+            };
+            // Older commits know the file by its previous name
+            if let Some(old_path) = &entry.old_path {
+                debug!(old_path, "Following rename");
+                path = old_path.clone();
             }
+            entries.push(entry);
         }
 
-        debug!(count = commits.len(), "File history retrieved");
-        Ok(commits)
+        debug!(count = entries.len(), "File history retrieved");
+        Ok(entries)
+    }
+
+    /// Cherry-pick each commit onto HEAD in the given order.
+    ///
+    /// Stops at the first commit that conflicts, leaving the cherry-pick in
+    /// progress for the conflict workflow; later commits are not applied.
+    #[instrument(skip(self))]
+    fn cherry_pick_commits(
+        &self,
+        shas: &[String],
+        mainline: Option<u32>,
+    ) -> GitResult<MergeResult> {
+        info!(count = shas.len(), "Cherry-picking commits");
+        apply_commits(self, shas, mainline, ApplyKind::CherryPick)
+    }
+
+    /// Revert each commit on top of HEAD in the given order.
+    ///
+    /// Conflicts are handled the same way as for `cherry_pick_commits`.
+    #[instrument(skip(self))]
+    fn revert_commits(&self, shas: &[String], mainline: Option<u32>) -> GitResult<MergeResult> {
+        info!(count = shas.len(), "Reverting commits");
+        apply_commits(self, shas, mainline, ApplyKind::Revert)
     }
 }
 
 // Helper functions
 
-fn commit_to_summary(commit: &Git2Commit) -> GitResult<CommitSummary> {
+/// Build a revision walk over the commits selected by a history range
+pub(crate) fn history_revwalk<'r>(
+    repo: &'r Git2Repository,
+    range: &HistoryRange,
+) -> GitResult<Revwalk<'r>> {
+    let mut revwalk = repo.revwalk()?;
+    revwalk.set_sorting(match range.order {
+        HistoryOrder::Date => Sort::TIME,
+        HistoryOrder::Topological => Sort::TOPOLOGICAL | Sort::TIME,
+    })?;
+
+    if range.revisions.is_empty() {
+        revwalk.push_head()?;
+    }
+    for revision in &range.revisions {
+        push_revision(repo, &mut revwalk, revision.trim()).map_err(|e| {
+            GitError::OperationFailed(format!("Invalid revision '{}': {}", revision, e.message()))
+        })?;
+    }
+
+    if range.first_parent {
+        revwalk.simplify_first_parent()?;
+    }
+    Ok(revwalk)
+}
+
+fn push_revision(
+    repo: &Git2Repository,
+    revwalk: &mut Revwalk,
+    revision: &str,
+) -> Result<(), git2::Error> {
+    if let Some(excluded) = revision.strip_prefix('^') {
+        let oid = repo.revparse_single(excluded)?.peel_to_commit()?.id();
+        return revwalk.hide(oid);
+    }
+
+    let spec = repo.revparse(revision)?;
+    let commit_id = |object: Option<&git2::Object>| -> Result<git2::Oid, git2::Error> {
+        match object {
+            Some(object) => Ok(object.peel_to_commit()?.id()),
+            None => Ok(repo.head()?.peel_to_commit()?.id()),
+        }
+    };
+
+    if spec.mode().contains(RevparseMode::MERGE_BASE) {
+        // A...B: commits reachable from either side but not from both
+        let from = commit_id(spec.from())?;
+        let to = commit_id(spec.to())?;
+        revwalk.push(from)?;
+        revwalk.push(to)?;
+        for base in repo.merge_bases(from, to)?.iter() {
+            revwalk.hide(*base)?;
+        }
+    } else if spec.mode().contains(RevparseMode::RANGE) {
+        // A..B: commits reachable from B but not from A; a missing side means HEAD
+        revwalk.hide(commit_id(spec.from())?)?;
+        revwalk.push(commit_id(spec.to())?)?;
+    } else {
+        revwalk.push(commit_id(spec.from())?)?;
+    }
+    Ok(())
+}
+
+pub(crate) fn commit_to_summary(commit: &Git2Commit) -> GitResult<CommitSummary> {
     let sha = commit.id().to_string();
     let short_sha = sha.chars().take(7).collect();
     let message = commit
@@ -183,40 +419,306 @@ fn commit_to_full(commit: &Git2Commit) -> GitResult<Commit> {
     })
 }
 
-fn commit_touches_file(
+/// How a commit changed one file
+struct FileChange {
+    old_path: Option<String>,
+    additions: usize,
+    deletions: usize,
+}
+
+/// Describe the change a commit made to a path, or `None` when the path has the
+/// same content as in one of the parents
+fn file_change(
     repo: &Git2Repository,
     commit: &Git2Commit,
-    file_path: &str,
-) -> GitResult<bool> {
+    path: &str,
+) -> GitResult<Option<FileChange>> {
     let tree = commit.tree()?;
+    let entry_id = tree_entry_id(&tree, path);
+    let parent_trees = commit
+        .parents()
+        .map(|parent| parent.tree())
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if parent_trees
+        .iter()
+        .any(|parent| tree_entry_id(parent, path) == entry_id)
+        || (parent_trees.is_empty() && entry_id.is_none())
+    {
+        return Ok(None);
+    }
+
+    let parent_tree = parent_trees.first();
+    let added = entry_id.is_some() && parent_tree.is_some_and(|t| tree_entry_id(t, path).is_none());
+
+    if !added {
+        // Modified, deleted or created in a root commit: only this path matters
+        let mut options = DiffOptions::new();
+        options.pathspec(path).disable_pathspec_match(true);
+        let diff = repo.diff_tree_to_tree(parent_tree, Some(&tree), Some(&mut options))?;
+        let (additions, deletions) = diff_line_stats(&diff, None)?;
+        return Ok(Some(FileChange {
+            old_path: None,
+            additions,
+            deletions,
+        }));
+    }
+
+    // The file appeared here, so it may have been renamed from another path
+    let mut diff = repo.diff_tree_to_tree(parent_tree, Some(&tree), None)?;
+    diff.find_similar(Some(DiffFindOptions::new().renames(true)))?;
+
+    let index = diff
+        .deltas()
+        .position(|delta| delta.new_file().path() == Some(Path::new(path)));
+    let old_path = index
+        .and_then(|i| diff.get_delta(i))
+        .filter(|delta| delta.status() == Delta::Renamed)
+        .and_then(|delta| delta.old_file().path())
+        .map(|p| p.to_string_lossy().replace('\\', "/"));
+    let (additions, deletions) = match index {
+        Some(index) => diff_line_stats(&diff, Some(index))?,
+        None => (0, 0),
+    };
+
+    Ok(Some(FileChange {
+        old_path,
+        additions,
+        deletions,
+    }))
+}
+
+fn tree_entry_id(tree: &Tree, path: &str) -> Option<git2::Oid> {
+    tree.get_path(Path::new(path)).ok().map(|entry| entry.id())
+}
+
+/// Added and deleted lines of one delta, or of the whole diff when no index is given
+fn diff_line_stats(diff: &Diff, index: Option<usize>) -> GitResult<(usize, usize)> {
+    let indices = match index {
+        Some(index) => index..index + 1,
+        None => 0..diff.deltas().len(),
+    };
+    let mut totals = (0, 0);
+    for i in indices {
+        if let Some(patch) = Patch::from_diff(diff, i)? {
+            let (_, additions, deletions) = patch.line_stats()?;
+            totals.0 += additions;
+            totals.1 += deletions;
+        }
+    }
+    Ok(totals)
+}
+
+/// Check a commit against the query, running the cheap metadata filters before
+/// any tree diff
+fn commit_matches(
+    repo: &Git2Repository,
+    commit: &Git2Commit,
+    query: &CommitSearchQuery,
+    message_pattern: Option<&Regex>,
+) -> GitResult<bool> {
+    let time = commit.time().seconds();
+    if query.since.is_some_and(|since| time < since.timestamp())
+        || query.until.is_some_and(|until| time > until.timestamp())
+    {
+        return Ok(false);
+    }
+
+    if let Some(author) = &query.author {
+        if !signature_matches(&commit.author(), author) {
+            return Ok(false);
+        }
+    }
+    if let Some(committer) = &query.committer {
+        if !signature_matches(&commit.committer(), committer) {
+            return Ok(false);
+        }
+    }
+
+    if let Some(pattern) = message_pattern {
+        if !pattern.is_match(commit.message().unwrap_or("")) {
+            return Ok(false);
+        }
+    }
 
+    if query.paths.is_empty() && query.content.is_none() {
+        return Ok(true);
+    }
+
+    let tree = commit.tree()?;
     let parent_tree = if commit.parent_count() > 0 {
         Some(commit.parent(0)?.tree()?)
     } else {
         None
     };
 
-    let diff = repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), None)?;
+    // Paths are literal, so `a[1].txt` does not also match `a1.txt`; a
+    // directory still matches the files below it
+    let mut options = DiffOptions::new();
+    options.disable_pathspec_match(true);
+    for path in &query.paths {
+        options.pathspec(path.replace('\\', "/"));
+    }
+    let diff = repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), Some(&mut options))?;
+
+    let Some(content) = query.content.as_deref().filter(|c| !c.is_empty()) else {
+        return Ok(diff.deltas().len() > 0);
+    };
+
+    // Like `git log -S`: the text was added or removed when its count changes
+    for delta in diff.deltas() {
+        let old_count = blob_occurrences(repo, delta.old_file().id(), content)?;
+        let new_count = blob_occurrences(repo, delta.new_file().id(), content)?;
+        if old_count != new_count {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+fn signature_matches(signature: &Signature, needle: &str) -> bool {
+    let needle = needle.to_lowercase();
+    [signature.name(), signature.email()]
+        .into_iter()
+        .flatten()
+        .any(|value| value.to_lowercase().contains(&needle))
+}
+
+fn blob_occurrences(repo: &Git2Repository, oid: git2::Oid, needle: &str) -> GitResult<usize> {
+    if oid.is_zero() {
+        return Ok(0);
+    }
+    let blob = match repo.find_blob(oid) {
+        Ok(blob) => blob,
+        // Submodule entries point at commits, not blobs
+        Err(_) => return Ok(0),
+    };
+    let needle = needle.as_bytes();
+    let content = blob.content();
+    let mut count = 0;
+    let mut start = 0;
+    while let Some(position) = content[start..]
+        .windows(needle.len())
+        .position(|window| window == needle)
+    {
+        count += 1;
+        start += position + needle.len();
+    }
+    Ok(count)
+}
 
-    let mut touches = false;
-    diff.foreach(
-        &mut |delta, _| {
-            if let Some(path) = delta.new_file().path() {
-                if path.to_string_lossy() == file_path {
-                    touches = true;
+fn apply_commits(
+    repo: &Git2Repository,
+    shas: &[String],
+    mainline: Option<u32>,
+    kind: ApplyKind,
+) -> GitResult<MergeResult> {
+    if shas.is_empty() {
+        return Err(GitError::OperationFailed(
+            "No commits were selected".to_string(),
+        ));
+    }
+    ensure_clean_index(repo)?;
+
+    let signature = repo.signature()?;
+    let mut last_commit = None;
+
+    for (position, sha) in shas.iter().enumerate() {
+        let commit = repo.revparse_single(sha)?.peel_to_commit()?;
+        if commit.parent_count() > 1 && mainline.is_none() {
+            return Err(GitError::OperationFailed(format!(
+                "Commit {} is a merge; a mainline parent is required",
+                commit.id()
+            )));
+        }
+        // git only accepts a mainline for merge commits
+        let mainline = mainline.filter(|_| commit.parent_count() > 1);
+
+        let mut checkout = git2::build::CheckoutBuilder::new();
+        checkout
+            .safe()
+            .allow_conflicts(true)
+            .conflict_style_merge(true);
+
+        match kind {
+            ApplyKind::CherryPick => {
+                let mut opts = git2::CherrypickOptions::new();
+                if let Some(parent) = mainline {
+                    opts.mainline(parent);
                 }
+                opts.checkout_builder(checkout);
+                repo.cherrypick(&commit, Some(&mut opts))?;
             }
-            if let Some(path) = delta.old_file().path() {
-                if path.to_string_lossy() == file_path {
-                    touches = true;
+            ApplyKind::Revert => {
+                let mut opts = git2::RevertOptions::new();
+                if let Some(parent) = mainline {
+                    opts.mainline(parent);
                 }
+                opts.checkout_builder(checkout);
+                repo.revert(&commit, Some(&mut opts))?;
             }
-            true
-        },
-        None,
-        None,
-        None,
-    )?;
+        }
+
+        let mut index = repo.index()?;
+        if index.has_conflicts() {
+            let conflicts = conflicted_paths(&index)?;
+            warn!(sha = %commit.id(), count = conflicts.len(), "Stopped with conflicts");
+            // The caller applies these again once the conflicts are resolved
+            return Ok(MergeResult {
+                success: false,
+                conflicts,
+                merged_commit: last_commit,
+                unapplied: shas[position + 1..].to_vec(),
+                squash_pending: false,
+            });
+        }
 
-    Ok(touches)
+        let tree = repo.find_tree(index.write_tree()?)?;
+        let head_commit = repo.head()?.peel_to_commit()?;
+        if tree.id() == head_commit.tree_id() {
+            // Nothing left to apply, e.g. the change is already on this branch
+            debug!(sha = %commit.id(), "Skipping empty commit");
+            repo.cleanup_state()?;
+            continue;
+        }
+
+        let oid = match kind {
+            ApplyKind::CherryPick => repo.commit(
+                Some("HEAD"),
+                &commit.author(),
+                &signature,
+                commit.message().unwrap_or(""),
+                &tree,
+                &[&head_commit],
+            )?,
+            ApplyKind::Revert => {
+                let message = format!(
+                    "Revert \"{}\"\n\nThis reverts commit {}.\n",
+                    commit.summary().unwrap_or(""),
+                    commit.id()
+                );
+                repo.commit(
+                    Some("HEAD"),
+                    &signature,
+                    &signature,
+                    &message,
+                    &tree,
+                    &[&head_commit],
+                )?
+            }
+        };
+        repo.cleanup_state()?;
+
+        debug!(sha = %commit.id(), commit = %oid, "Commit applied");
+        last_commit = Some(oid.to_string());
+    }
+
+    info!("All commits applied");
+    Ok(MergeResult {
+        success: true,
+        conflicts: Vec::new(),
+        merged_commit: last_commit,
+        unapplied: Vec::new(),
+        squash_pending: false,
+    })
 }
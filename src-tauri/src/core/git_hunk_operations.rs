@@ -0,0 +1,423 @@
+use crate::core::git_discard_operations::backup_workdir_files;
+use crate::core::git_error::{GitError, GitResult};
+use crate::models::git_repository::{DiffHunk, DiffLineType, DiscardResult};
+use git2::{ApplyLocation, Diff, Repository as Git2Repository};
+use std::path::Path;
+use tracing::{debug, info, instrument};
+
+/// Extension trait for GitEngine to stage, unstage and discard parts of a file.
+///
+/// Hunks are the ones returned by `get_file_diff_unstaged` (stage, discard) and
+/// `get_file_diff_staged` (unstage). `selected_lines` holds indices into
+/// `hunk.lines`; `None` applies the whole hunk.
+pub trait GitHunkOperations {
+    fn stage_hunk(
+        &self,
+        file_path: &str,
+        hunk: &DiffHunk,
+        selected_lines: Option<&[usize]>,
+    ) -> GitResult<()>;
+    fn unstage_hunk(
+        &self,
+        file_path: &str,
+        hunk: &DiffHunk,
+        selected_lines: Option<&[usize]>,
+    ) -> GitResult<()>;
+    fn discard_hunk(
+        &self,
+        file_path: &str,
+        hunk: &DiffHunk,
+        selected_lines: Option<&[usize]>,
+    ) -> GitResult<DiscardResult>;
+}
+
+impl GitHunkOperations for Git2Repository {
+    #[instrument(skip(self, hunk, selected_lines), fields(header = %hunk.header.trim_end()))]
+    fn stage_hunk(
+        &self,
+        file_path: &str,
+        hunk: &DiffHunk,
+        selected_lines: Option<&[usize]>,
+    ) -> GitResult<()> {
+        info!("Staging hunk");
+        let path_str = file_path.replace('\\', "/");
+        if self.index()?.get_path(Path::new(&path_str), 0).is_none() {
+            return Err(GitError::OperationFailed(format!(
+                "'{}' is not tracked; stage the whole file instead",
+                path_str
+            )));
+        }
+
+        let patch = build_patch(&path_str, hunk, selected_lines, false)?;
+        apply_patch(self, &patch, ApplyLocation::Index)?;
+        info!("Hunk staged");
+        Ok(())
+    }
+
+    #[instrument(skip(self, hunk, selected_lines), fields(header = %hunk.header.trim_end()))]
+    fn unstage_hunk(
+        &self,
+        file_path: &str,
+        hunk: &DiffHunk,
+        selected_lines: Option<&[usize]>,
+    ) -> GitResult<()> {
+        info!("Unstaging hunk");
+        let path_str = file_path.replace('\\', "/");
+        let head_tree = self.head()?.peel_to_tree()?;
+        if head_tree.get_path(Path::new(&path_str)).is_err() {
+            return Err(GitError::OperationFailed(format!(
+                "'{}' is new in the index; unstage the whole file instead",
+                path_str
+            )));
+        }
+
+        let patch = build_patch(&path_str, hunk, selected_lines, true)?;
+        apply_patch(self, &patch, ApplyLocation::Index)?;
+        info!("Hunk unstaged");
+        Ok(())
+    }
+
+    #[instrument(skip(self, hunk, selected_lines), fields(header = %hunk.header.trim_end()))]
+    fn discard_hunk(
+        &self,
+        file_path: &str,
+        hunk: &DiffHunk,
+        selected_lines: Option<&[usize]>,
+    ) -> GitResult<DiscardResult> {
+        info!("Discarding hunk");
+        let path_str = file_path.replace('\\', "/");
+        let patch = build_patch(&path_str, hunk, selected_lines, true)?;
+
+        let backup_ref =
+            backup_workdir_files(self, std::slice::from_ref(&path_str), "discarding a hunk")?;
+        apply_patch(self, &patch, ApplyLocation::WorkDir)?;
+
+        info!("Hunk discarded");
+        Ok(DiscardResult {
+            paths: vec![path_str],
+            backup_ref,
+            dry_run: false,
+        })
+    }
+}
+
+// Helper functions
+
+fn apply_patch(repo: &Git2Repository, patch: &str, location: ApplyLocation) -> GitResult<()> {
+    let diff = Diff::from_buffer(patch.as_bytes())?;
+    repo.apply(&diff, location, None).map_err(|e| {
+        GitError::OperationFailed(format!(
+            "Hunk no longer matches the file; refresh the diff and try again ({})",
+            e.message()
+        ))
+    })
+}
+
+/// Build a single-hunk unified diff for `path` containing only the selected lines.
+///
+/// With `reverse` set the patch undoes the hunk, so it applies to the hunk's new
+/// side (the index for unstaging, the working tree for discarding). Unselected
+/// lines that exist on the side being patched become context; the rest are dropped.
+fn build_patch(
+    path: &str,
+    hunk: &DiffHunk,
+    selected_lines: Option<&[usize]>,
+    reverse: bool,
+) -> GitResult<String> {
+    let mut body = String::new();
+    // Removals of a change run are written before its additions, as git does;
+    // a reversed hunk would otherwise list them the wrong way round
+    let mut removed = String::new();
+    let mut added_lines = String::new();
+    let mut old_count = 0;
+    let mut new_count = 0;
+    let mut changes = 0;
+
+    for (index, line) in hunk.lines.iter().enumerate() {
+        // libgit2 reports "\ No newline at end of file" as its own line; it is
+        // re-added below from the line content instead
+        if line.content.starts_with("\n\\") {
+            continue;
+        }
+
+        let (added, deleted) = match line.origin {
+            DiffLineType::Addition => (!reverse, reverse),
+            DiffLineType::Deletion => (reverse, !reverse),
+            DiffLineType::Context => (false, false),
+            _ => continue,
+        };
+        let selected = selected_lines.is_none_or(|lines| lines.contains(&index));
+
+        if added {
+            if selected {
+                new_count += 1;
+                changes += 1;
+                push_line(&mut added_lines, '+', &line.content);
+            }
+        } else if deleted && selected {
+            old_count += 1;
+            changes += 1;
+            push_line(&mut removed, '-', &line.content);
+        } else {
+            body.push_str(&removed);
+            body.push_str(&added_lines);
+            removed.clear();
+            added_lines.clear();
+            old_count += 1;
+            new_count += 1;
+            push_line(&mut body, ' ', &line.content);
+        }
+    }
+    body.push_str(&removed);
+    body.push_str(&added_lines);
+
+    if changes == 0 {
+        return Err(GitError::OperationFailed(
+            "No changed lines were selected".to_string(),
+        ));
+    }
+
+    let old_start = if reverse {
+        hunk.new_start
+    } else {
+        hunk.old_start
+    };
+    // libgit2 applies a hunk at exactly `new_start - 1`, so an empty new side
+    // keeps the preimage position; only a hunk from line 1 becomes `+0,0`
+    let new_start = if old_count == 0 {
+        old_start + 1
+    } else if new_count == 0 && old_start <= 1 {
+        0
+    } else {
+        old_start
+    };
+
+    debug!(old_count, new_count, changes, "Patch built");
+    Ok(format!(
+        "diff --git a/{path} b/{path}\n--- a/{path}\n+++ b/{path}\n@@ -{old_start},{old_count} +{new_start},{new_count} @@\n{body}",
+    ))
+}
+
+fn push_line(patch: &mut String, prefix: char, content: &str) {
+    patch.push(prefix);
+    patch.push_str(content);
+    if !content.ends_with('\n') {
+        patch.push_str("\n\\ No newline at end of file\n");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::git_diff_operations::GitDiffOperations;
+    use crate::models::git_repository::{DiffLine, DiffRequestOptions};
+    use std::fs;
+    use std::path::PathBuf;
+
+    const EOF_MARKER: &str = "\n\\ No newline at end of file\n";
+
+    fn line(origin: DiffLineType, content: &str) -> DiffLine {
+        DiffLine {
+            origin,
+            content: content.to_string(),
+            old_lineno: None,
+            new_lineno: None,
+            word_ranges: Vec::new(),
+        }
+    }
+
+    /// "two" changed to "TWO" and "four" appended after "three"
+    fn hunk() -> DiffHunk {
+        DiffHunk {
+            old_start: 1,
+            old_lines: 3,
+            new_start: 1,
+            new_lines: 4,
+            header: "@@ -1,3 +1,4 @@".to_string(),
+            lines: vec![
+                line(DiffLineType::Context, "one\n"),
+                line(DiffLineType::Deletion, "two\n"),
+                line(DiffLineType::Addition, "TWO\n"),
+                line(DiffLineType::Context, "three\n"),
+                line(DiffLineType::Addition, "four\n"),
+            ],
+        }
+    }
+
+    fn header(path: &str) -> String {
+        format!("diff --git a/{path} b/{path}\n--- a/{path}\n+++ b/{path}\n")
+    }
+
+    #[test]
+    fn forward_selection_keeps_unselected_deletions_as_context() {
+        let patch = build_patch("f.txt", &hunk(), Some(&[2]), false).unwrap();
+        assert_eq!(
+            patch,
+            header("f.txt") + "@@ -1,3 +1,4 @@\n one\n two\n+TWO\n three\n"
+        );
+    }
+
+    #[test]
+    fn forward_selection_of_a_deletion_drops_unselected_additions() {
+        let patch = build_patch("f.txt", &hunk(), Some(&[1]), false).unwrap();
+        assert_eq!(
+            patch,
+            header("f.txt") + "@@ -1,3 +1,2 @@\n one\n-two\n three\n"
+        );
+    }
+
+    #[test]
+    fn reverse_selection_applies_to_the_new_side() {
+        // Unstaging "TWO" keeps "four", which is on the side being patched
+        let patch = build_patch("f.txt", &hunk(), Some(&[2]), true).unwrap();
+        assert_eq!(
+            patch,
+            header("f.txt") + "@@ -1,4 +1,3 @@\n one\n-TWO\n three\n four\n"
+        );
+    }
+
+    #[test]
+    fn reverse_whole_hunk_writes_removals_before_additions() {
+        let patch = build_patch("f.txt", &hunk(), None, true).unwrap();
+        assert_eq!(
+            patch,
+            header("f.txt") + "@@ -1,4 +1,3 @@\n one\n-TWO\n+two\n three\n-four\n"
+        );
+    }
+
+    #[test]
+    fn missing_newline_at_end_of_file_is_marked() {
+        let hunk = DiffHunk {
+            old_start: 1,
+            old_lines: 2,
+            new_start: 1,
+            new_lines: 2,
+            header: "@@ -1,2 +1,2 @@".to_string(),
+            lines: vec![
+                line(DiffLineType::Context, "a\n"),
+                line(DiffLineType::Deletion, "b"),
+                line(DiffLineType::Context, EOF_MARKER),
+                line(DiffLineType::Addition, "b\n"),
+            ],
+        };
+
+        let patch = build_patch("f.txt", &hunk, None, false).unwrap();
+        assert_eq!(
+            patch,
+            header("f.txt") + "@@ -1,2 +1,2 @@\n a\n-b\n\\ No newline at end of file\n+b\n"
+        );
+
+        // Reversed, the line without a newline is the one being added back
+        let patch = build_patch("f.txt", &hunk, None, true).unwrap();
+        assert_eq!(
+            patch,
+            header("f.txt") + "@@ -1,2 +1,2 @@\n a\n-b\n+b\n\\ No newline at end of file\n"
+        );
+    }
+
+    #[test]
+    fn selecting_only_context_is_rejected() {
+        assert!(build_patch("f.txt", &hunk(), Some(&[0, 3]), false).is_err());
+    }
+
+    const LINES: &str = "1\n2\n3\n4\n5\n6\n";
+
+    /// Fresh repository in the temp directory with `LINES` committed as f.txt
+    fn repo(name: &str) -> (Git2Repository, PathBuf) {
+        let dir = std::env::temp_dir().join(format!("navin-hunk-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let repo = Git2Repository::init(&dir).unwrap();
+        let mut config = repo.config().unwrap();
+        config.set_str("user.name", "Navin").unwrap();
+        config.set_str("user.email", "navin@example.com").unwrap();
+
+        fs::write(dir.join("f.txt"), LINES).unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("f.txt")).unwrap();
+        index.write().unwrap();
+        let tree_id = index.write_tree().unwrap();
+        {
+            let tree = repo.find_tree(tree_id).unwrap();
+            let signature = repo.signature().unwrap();
+            repo.commit(Some("HEAD"), &signature, &signature, "init", &tree, &[])
+                .unwrap();
+        }
+        (repo, dir)
+    }
+
+    fn zero_context() -> DiffRequestOptions {
+        DiffRequestOptions {
+            context_lines: Some(0),
+            ..Default::default()
+        }
+    }
+
+    fn staged_content(repo: &Git2Repository) -> String {
+        let entry = repo
+            .index()
+            .unwrap()
+            .get_path(Path::new("f.txt"), 0)
+            .unwrap();
+        let blob = repo.find_blob(entry.id).unwrap();
+        String::from_utf8_lossy(blob.content()).to_string()
+    }
+
+    #[test]
+    fn stages_a_deletion_without_context() {
+        let (repo, dir) = repo("stage-deletion");
+        fs::write(dir.join("f.txt"), "1\n2\n3\n5\n6\n").unwrap();
+
+        let diff = repo
+            .get_file_diff_unstaged("f.txt", &zero_context())
+            .unwrap();
+        assert_eq!(diff.hunks[0].header.trim_end(), "@@ -4 +3,0 @@");
+        repo.stage_hunk("f.txt", &diff.hunks[0], None).unwrap();
+
+        assert_eq!(staged_content(&repo), "1\n2\n3\n5\n6\n");
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn stages_a_deletion_of_the_first_line() {
+        let (repo, dir) = repo("stage-first");
+        fs::write(dir.join("f.txt"), "2\n3\n4\n5\n6\n").unwrap();
+
+        let diff = repo
+            .get_file_diff_unstaged("f.txt", &zero_context())
+            .unwrap();
+        repo.stage_hunk("f.txt", &diff.hunks[0], None).unwrap();
+
+        assert_eq!(staged_content(&repo), "2\n3\n4\n5\n6\n");
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn unstages_a_deletion_without_context() {
+        let (repo, dir) = repo("unstage-deletion");
+        fs::write(dir.join("f.txt"), "1\n2\n3\n5\n6\n").unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("f.txt")).unwrap();
+        index.write().unwrap();
+
+        let diff = repo.get_file_diff_staged("f.txt", &zero_context()).unwrap();
+        repo.unstage_hunk("f.txt", &diff.hunks[0], None).unwrap();
+
+        assert_eq!(staged_content(&repo), LINES);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn discards_an_addition_without_context() {
+        let (repo, dir) = repo("discard-addition");
+        fs::write(dir.join("f.txt"), "1\n2\n3\nnew\n4\n5\n6\n").unwrap();
+
+        let diff = repo
+            .get_file_diff_unstaged("f.txt", &zero_context())
+            .unwrap();
+        assert_eq!(diff.hunks[0].header.trim_end(), "@@ -3,0 +4 @@");
+        repo.discard_hunk("f.txt", &diff.hunks[0], None).unwrap();
+
+        assert_eq!(fs::read_to_string(dir.join("f.txt")).unwrap(), LINES);
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
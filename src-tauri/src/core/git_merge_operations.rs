@@ -0,0 +1,311 @@
+use crate::core::git_error::{GitError, GitResult};
+use crate::models::git_repository::{MergeMode, MergeResult, MergeStrategy};
+use git2::{AnnotatedCommit, Index, Repository as Git2Repository, Tree};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use tracing::{debug, info, instrument, warn};
+
+/// File in the git directory marking a squash merge stopped with conflicts;
+/// git records no state for a squash, so the conflict workflow relies on it
+const SQUASH_STATE_FILE: &str = "navin-squash.json";
+
+/// Squash merge waiting for its conflicts to be resolved
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct PendingSquash {
+    /// HEAD when the squash started; once HEAD moves the squash was finished
+    /// with a regular commit
+    pub(crate) orig_head: String,
+    pub(crate) message: String,
+}
+
+/// Extension trait for GitEngine to handle branch merges
+pub trait GitMergeOperations {
+    fn merge_branch(
+        &self,
+        source: &str,
+        mode: MergeMode,
+        strategy: Option<MergeStrategy>,
+        message: Option<&str>,
+    ) -> GitResult<MergeResult>;
+}
+
+impl GitMergeOperations for Git2Repository {
+    /// Merge a local branch (or any revision) into the current branch
+    #[instrument(skip(self, message))]
+    fn merge_branch(
+        &self,
+        source: &str,
+        mode: MergeMode,
+        strategy: Option<MergeStrategy>,
+        message: Option<&str>,
+    ) -> GitResult<MergeResult> {
+        info!("Merging branch");
+        let annotated = match self.find_branch(source, git2::BranchType::Local) {
+            Ok(branch) => self.reference_to_annotated_commit(branch.get())?,
+            Err(_) => {
+                let commit = self.revparse_single(source)?.peel_to_commit()?;
+                self.find_annotated_commit(commit.id())?
+            }
+        };
+
+        let default_message = match mode {
+            MergeMode::Squash => format!("Squash merge branch '{}'", source),
+            _ => format!("Merge branch '{}'", source),
+        };
+        let message = message.unwrap_or(&default_message);
+
+        merge_annotated_commit(self, &annotated, mode, strategy, message)
+    }
+}
+
+// Helper functions
+
+/// Merge an annotated commit into HEAD, creating the merge commit when there are
+/// no conflicts. Conflicted merges are left in progress for the conflict workflow.
+pub(crate) fn merge_annotated_commit(
+    repo: &Git2Repository,
+    annotated: &AnnotatedCommit,
+    mode: MergeMode,
+    strategy: Option<MergeStrategy>,
+    message: &str,
+) -> GitResult<MergeResult> {
+    ensure_supported_strategy(strategy.as_ref())?;
+    if pending_squash(repo)?.is_some() {
+        return Err(GitError::OperationFailed(
+            "A squash merge is in progress; commit or abort it first".to_string(),
+        ));
+    }
+    let (analysis, _) = repo.merge_analysis(&[annotated])?;
+
+    if analysis.is_up_to_date() {
+        info!("Already up to date");
+        return Ok(MergeResult {
+            success: true,
+            conflicts: Vec::new(),
+            merged_commit: None,
+            unapplied: Vec::new(),
+            squash_pending: false,
+        });
+    }
+
+    let can_fast_forward = analysis.is_fast_forward() || analysis.is_unborn();
+    match mode {
+        MergeMode::FastForward | MergeMode::FastForwardOnly if can_fast_forward => {
+            return fast_forward(repo, annotated);
+        }
+        MergeMode::FastForwardOnly => {
+            return Err(GitError::OperationFailed(
+                "Branches have diverged; cannot fast-forward".to_string(),
+            ));
+        }
+        _ => {}
+    }
+
+    ensure_clean_index(repo)?;
+
+    // The ours strategy records the merge but keeps HEAD's tree as it is
+    if matches!(strategy, Some(MergeStrategy::Ours)) {
+        if matches!(mode, MergeMode::Squash) {
+            info!("Nothing to squash with the ours strategy");
+            return Ok(MergeResult {
+                success: true,
+                conflicts: Vec::new(),
+                merged_commit: None,
+                unapplied: Vec::new(),
+                squash_pending: false,
+            });
+        }
+        let tree = repo.head()?.peel_to_tree()?;
+        return commit_merge(repo, annotated, mode, &tree, message);
+    }
+
+    let mut merge_opts = git2::MergeOptions::new();
+    if matches!(strategy, Some(MergeStrategy::Resolve)) {
+        // Like git's resolve strategy, use a single merge base
+        merge_opts.no_recursive(true);
+    }
+
+    let mut checkout = git2::build::CheckoutBuilder::new();
+    checkout
+        .safe()
+        .allow_conflicts(true)
+        .conflict_style_merge(true);
+
+    repo.merge(&[annotated], Some(&mut merge_opts), Some(&mut checkout))?;
+
+    let mut index = repo.index()?;
+    if index.has_conflicts() {
+        let conflicts = conflicted_paths(&index)?;
+        let squash_pending = matches!(mode, MergeMode::Squash);
+        if squash_pending {
+            // A squash never records MERGE_HEAD, so the resolution ends in a regular commit
+            repo.cleanup_state()?;
+            record_pending_squash(repo, message)?;
+        }
+        warn!(count = conflicts.len(), "Merge stopped with conflicts");
+        return Ok(MergeResult {
+            success: false,
+            conflicts,
+            merged_commit: None,
+            unapplied: Vec::new(),
+            squash_pending,
+        });
+    }
+
+    let tree = repo.find_tree(index.write_tree()?)?;
+    commit_merge(repo, annotated, mode, &tree, message)
+}
+
+/// Record `tree` as the merge of `annotated` into HEAD; a squash gets HEAD as
+/// its only parent
+fn commit_merge(
+    repo: &Git2Repository,
+    annotated: &AnnotatedCommit,
+    mode: MergeMode,
+    tree: &Tree,
+    message: &str,
+) -> GitResult<MergeResult> {
+    let signature = repo.signature()?;
+    let head_commit = repo.head()?.peel_to_commit()?;
+    let their_commit = repo.find_commit(annotated.id())?;
+
+    let parents = match mode {
+        MergeMode::Squash => vec![&head_commit],
+        _ => vec![&head_commit, &their_commit],
+    };
+
+    let oid = repo.commit(
+        Some("HEAD"),
+        &signature,
+        &signature,
+        message,
+        tree,
+        &parents,
+    )?;
+    repo.cleanup_state()?;
+
+    info!(commit = %oid, "Merge commit created");
+    Ok(MergeResult {
+        success: true,
+        conflicts: Vec::new(),
+        merged_commit: Some(oid.to_string()),
+        unapplied: Vec::new(),
+        squash_pending: false,
+    })
+}
+
+fn record_pending_squash(repo: &Git2Repository, message: &str) -> GitResult<()> {
+    let state = PendingSquash {
+        orig_head: repo.head()?.peel_to_commit()?.id().to_string(),
+        message: message.to_string(),
+    };
+    let content = serde_json::to_string(&state)
+        .map_err(|e| GitError::OperationFailed(format!("Could not save squash state: {}", e)))?;
+    fs::write(repo.path().join(SQUASH_STATE_FILE), content)?;
+    debug!("Squash merge marked as pending");
+    Ok(())
+}
+
+/// The squash merge waiting for conflict resolution, if any. A marker left
+/// behind after HEAD moved on is removed.
+pub(crate) fn pending_squash(repo: &Git2Repository) -> GitResult<Option<PendingSquash>> {
+    let path = repo.path().join(SQUASH_STATE_FILE);
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let content = fs::read_to_string(&path)?;
+    let state: PendingSquash = serde_json::from_str(&content)
+        .map_err(|e| GitError::OperationFailed(format!("Invalid squash merge state: {}", e)))?;
+    let head = repo.head().ok().and_then(|head| head.target());
+    if head.map(|oid| oid.to_string()).as_deref() != Some(state.orig_head.as_str()) {
+        debug!("Removing stale squash marker");
+        fs::remove_file(&path)?;
+        return Ok(None);
+    }
+    Ok(Some(state))
+}
+
+pub(crate) fn clear_pending_squash(repo: &Git2Repository) -> GitResult<()> {
+    let path = repo.path().join(SQUASH_STATE_FILE);
+    if path.exists() {
+        fs::remove_file(path)?;
+    }
+    Ok(())
+}
+
+/// Only strategies that apply to merging a single branch are supported
+fn ensure_supported_strategy(strategy: Option<&MergeStrategy>) -> GitResult<()> {
+    let name = match strategy {
+        Some(MergeStrategy::Theirs) => "theirs",
+        Some(MergeStrategy::Octopus) => "octopus",
+        _ => return Ok(()),
+    };
+    warn!(strategy = name, "Unsupported merge strategy");
+    Err(GitError::OperationFailed(format!(
+        "The {} strategy is not supported when merging a single branch",
+        name
+    )))
+}
+
+/// Collect the paths of every conflicted entry in the index
+pub(crate) fn conflicted_paths(index: &Index) -> GitResult<Vec<String>> {
+    let mut paths = Vec::new();
+    for conflict in index.conflicts()? {
+        let conflict = conflict?;
+        let entry = conflict
+            .our
+            .as_ref()
+            .or(conflict.their.as_ref())
+            .or(conflict.ancestor.as_ref());
+        if let Some(entry) = entry {
+            paths.push(String::from_utf8_lossy(&entry.path).to_string());
+        }
+    }
+    Ok(paths)
+}
+
+fn fast_forward(repo: &Git2Repository, annotated: &AnnotatedCommit) -> GitResult<MergeResult> {
+    info!("Fast-forward merge");
+    let target = repo.find_object(annotated.id(), None)?;
+    repo.checkout_tree(&target, Some(git2::build::CheckoutBuilder::new().safe()))?;
+
+    let head = repo.find_reference("HEAD")?;
+    let refname = head
+        .symbolic_target()
+        .map(String::from)
+        .unwrap_or_else(|| "HEAD".to_string());
+    repo.reference(&refname, annotated.id(), true, "merge: Fast-forward")?;
+
+    debug!(target = %annotated.id(), "Fast-forwarded");
+    Ok(MergeResult {
+        success: true,
+        conflicts: Vec::new(),
+        merged_commit: Some(annotated.id().to_string()),
+        unapplied: Vec::new(),
+        squash_pending: false,
+    })
+}
+
+/// Fail when the index has staged changes that a merge would mix into its commit
+pub(crate) fn ensure_clean_index(repo: &Git2Repository) -> GitResult<()> {
+    let statuses = repo.statuses(None)?;
+    let staged: Vec<String> = statuses
+        .iter()
+        .filter(|entry| {
+            let status = entry.status();
+            status.is_index_new()
+                || status.is_index_modified()
+                || status.is_index_deleted()
+                || status.is_index_renamed()
+                || status.is_index_typechange()
+        })
+        .filter_map(|entry| entry.path().map(String::from))
+        .collect();
+
+    if staged.is_empty() {
+        Ok(())
+    } else {
+        Err(GitError::DirtyWorkingTree(staged.join(", ")))
+    }
+}
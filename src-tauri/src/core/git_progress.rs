@@ -0,0 +1,155 @@
+use crate::models::git_repository::{TransferProgress, TransferStage};
+use git2::build::CheckoutBuilder;
+use git2::{PackBuilderStage, RemoteCallbacks};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Minimum time between two progress events of the same kind
+const EMIT_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Receiver for progress events of a network operation
+pub type ProgressSink = Arc<dyn Fn(TransferProgress) + Send + Sync>;
+
+/// Progress reporting and cancellation for one fetch, push, pull or clone.
+///
+/// Cancelling aborts a download at the next progress callback. libgit2 offers no
+/// way to stop a pack upload once it has started, so a push can only be cancelled
+/// before the upload or while the remote is still reporting.
+#[derive(Clone)]
+pub struct OperationProgress {
+    id: String,
+    cancelled: Arc<AtomicBool>,
+    sink: ProgressSink,
+}
+
+impl OperationProgress {
+    pub fn new(id: impl Into<String>, sink: ProgressSink) -> Self {
+        Self {
+            id: id.into(),
+            cancelled: Arc::new(AtomicBool::new(false)),
+            sink,
+        }
+    }
+
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Shared flag that cancels the operation when set
+    pub fn cancel_flag(&self) -> Arc<AtomicBool> {
+        Arc::clone(&self.cancelled)
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn emit(
+        &self,
+        stage: TransferStage,
+        current: usize,
+        total: usize,
+        bytes: usize,
+        message: Option<String>,
+    ) {
+        (self.sink)(TransferProgress {
+            operation_id: self.id.clone(),
+            stage,
+            current,
+            total,
+            bytes,
+            message,
+        });
+    }
+
+    /// Register transfer, sideband and packing callbacks that report progress
+    /// and stop the transfer once the operation is cancelled.
+    pub(crate) fn attach(&self, callbacks: &mut RemoteCallbacks<'_>) {
+        let progress = self.clone();
+        let mut last_emit: Option<Instant> = None;
+        callbacks.transfer_progress(move |stats| {
+            let (stage, current, total) = if stats.received_objects() < stats.total_objects() {
+                (
+                    TransferStage::Receiving,
+                    stats.received_objects(),
+                    stats.total_objects(),
+                )
+            } else {
+                (
+                    TransferStage::Resolving,
+                    stats.indexed_deltas(),
+                    stats.total_deltas(),
+                )
+            };
+            if should_emit(&mut last_emit, current, total) {
+                progress.emit(stage, current, total, stats.received_bytes(), None);
+            }
+            !progress.is_cancelled()
+        });
+
+        let progress = self.clone();
+        callbacks.sideband_progress(move |data| {
+            let text = String::from_utf8_lossy(data).trim().to_string();
+            if !text.is_empty() {
+                progress.emit(TransferStage::Remote, 0, 0, 0, Some(text));
+            }
+            !progress.is_cancelled()
+        });
+
+        let progress = self.clone();
+        let mut last_emit: Option<Instant> = None;
+        callbacks.pack_progress(move |stage, current, total| {
+            if matches!(
+                stage,
+                PackBuilderStage::AddingObjects | PackBuilderStage::Deltafication
+            ) && should_emit(&mut last_emit, current, total)
+            {
+                progress.emit(TransferStage::Packing, current, total, 0, None);
+            }
+        });
+
+        let progress = self.clone();
+        let mut last_emit: Option<Instant> = None;
+        callbacks.push_transfer_progress(move |current, total, bytes| {
+            if should_emit(&mut last_emit, current, total) {
+                progress.emit(TransferStage::Uploading, current, total, bytes, None);
+            }
+        });
+
+        let progress = self.clone();
+        callbacks.push_negotiation(move |_updates| {
+            if progress.is_cancelled() {
+                Err(git2::Error::from_str("Operation cancelled"))
+            } else {
+                Ok(())
+            }
+        });
+    }
+
+    /// Report files written during a checkout
+    pub(crate) fn attach_checkout(&self, checkout: &mut CheckoutBuilder<'_>) {
+        let progress = self.clone();
+        let mut last_emit: Option<Instant> = None;
+        checkout.progress(move |path, current, total| {
+            if should_emit(&mut last_emit, current, total) {
+                let message = path.map(|p| p.to_string_lossy().replace('\\', "/"));
+                progress.emit(TransferStage::CheckingOut, current, total, 0, message);
+            }
+        });
+    }
+}
+
+// Helper functions
+
+/// Throttle frequent callbacks, always letting the final update through
+fn should_emit(last_emit: &mut Option<Instant>, current: usize, total: usize) -> bool {
+    let now = Instant::now();
+    let due = last_emit.is_none_or(|last| now.duration_since(last) >= EMIT_INTERVAL);
+    if due || current == total {
+        *last_emit = Some(now);
+        true
+    } else {
+        false
+    }
+}
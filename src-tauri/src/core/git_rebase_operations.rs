@@ -0,0 +1,523 @@
+use crate::core::git_error::{dirty_paths, GitError, GitResult};
+use crate::core::git_history_operations::commit_to_summary;
+use crate::core::git_merge_operations::{conflicted_paths, pending_squash};
+use crate::models::git_repository::{CommitSummary, RebaseAction, RebasePlanStep, RebaseStatus};
+use git2::{
+    Commit as Git2Commit, ErrorCode, Oid, Rebase, Repository as Git2Repository, RepositoryState,
+    ResetType, Signature,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::path::PathBuf;
+use tracing::{debug, info, instrument, warn};
+
+/// Sequencer state file, stored inside the git directory while a plan is applied
+const STATE_FILE: &str = "navin-rebase.json";
+
+/// Extension trait for GitEngine to handle interactive rebases.
+///
+/// libgit2's rebase only replays commits in their original order, so interactive
+/// plans are applied by a cherry-pick sequencer whose progress is persisted in
+/// the git directory. Continue, skip and abort also drive a plain `git2::Rebase`
+/// left in progress, such as one started by a rebase pull.
+pub trait GitRebaseOperations {
+    fn get_rebase_todo(&self, onto: &str) -> GitResult<Vec<CommitSummary>>;
+    fn start_interactive_rebase(
+        &self,
+        onto: &str,
+        plan: Vec<RebasePlanStep>,
+    ) -> GitResult<RebaseStatus>;
+    fn continue_rebase(&self) -> GitResult<RebaseStatus>;
+    fn skip_rebase_step(&self) -> GitResult<RebaseStatus>;
+    fn abort_rebase(&self) -> GitResult<()>;
+    fn get_rebase_status(&self) -> GitResult<RebaseStatus>;
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct SequencerState {
+    head_name: Option<String>,
+    orig_head: String,
+    onto: String,
+    steps: Vec<RebasePlanStep>,
+    next: usize,
+}
+
+impl GitRebaseOperations for Git2Repository {
+    /// Commits that a rebase onto `onto` would replay, oldest first
+    #[instrument(skip(self))]
+    fn get_rebase_todo(&self, onto: &str) -> GitResult<Vec<CommitSummary>> {
+        info!("Building rebase todo list");
+        let onto_oid = self.revparse_single(onto)?.peel_to_commit()?.id();
+
+        let mut revwalk = self.revwalk()?;
+        revwalk.push_head()?;
+        revwalk.hide(onto_oid)?;
+        revwalk.set_sorting(git2::Sort::TOPOLOGICAL | git2::Sort::REVERSE)?;
+
+        let mut commits = Vec::new();
+        for oid in revwalk {
+            let commit = self.find_commit(oid?)?;
+            commits.push(commit_to_summary(&commit)?);
+        }
+
+        debug!(count = commits.len(), "Rebase todo list built");
+        Ok(commits)
+    }
+
+    #[instrument(skip(self, plan), fields(steps = plan.len()))]
+    fn start_interactive_rebase(
+        &self,
+        onto: &str,
+        plan: Vec<RebasePlanStep>,
+    ) -> GitResult<RebaseStatus> {
+        info!("Starting interactive rebase");
+        if load_state(self)?.is_some()
+            || pending_squash(self)?.is_some()
+            || self.state() != RepositoryState::Clean
+        {
+            return Err(GitError::OperationFailed(
+                "Another merge or rebase is already in progress".to_string(),
+            ));
+        }
+        let dirty = dirty_paths(self)?;
+        if !dirty.is_empty() {
+            return Err(GitError::DirtyWorkingTree(dirty.join(", ")));
+        }
+
+        let onto_commit = self.revparse_single(onto)?.peel_to_commit()?;
+        let steps = validate_plan(self, onto_commit.id(), plan)?;
+
+        let head = self.head()?;
+        let head_name = if head.is_branch() {
+            head.name().map(String::from)
+        } else {
+            None
+        };
+        let orig_head = head.peel_to_commit()?.id();
+
+        // Work on a detached HEAD so the branch only moves once the plan completes
+        self.checkout_tree(
+            onto_commit.as_object(),
+            Some(git2::build::CheckoutBuilder::new().safe()),
+        )?;
+        self.set_head_detached(onto_commit.id())?;
+
+        let state = SequencerState {
+            head_name,
+            orig_head: orig_head.to_string(),
+            onto: onto_commit.id().to_string(),
+            steps,
+            next: 0,
+        };
+        save_state(self, &state)?;
+
+        run_sequencer(self, state)
+    }
+
+    #[instrument(skip(self))]
+    fn continue_rebase(&self) -> GitResult<RebaseStatus> {
+        info!("Continuing rebase");
+        ensure_no_conflicts(self)?;
+
+        if let Some(mut state) = load_state(self)? {
+            // Commit the step that stopped on conflicts, now that it is resolved
+            if self.state() == RepositoryState::CherryPick {
+                let step = state.steps[state.next].clone();
+                let commit = self.find_commit(Oid::from_str(&step.sha)?)?;
+                commit_step(self, &step, &commit, &self.signature()?)?;
+            }
+            state.next += 1;
+            save_state(self, &state)?;
+            return run_sequencer(self, state);
+        }
+
+        if is_git_rebase(self) {
+            let signature = self.signature()?;
+            let mut rebase = self.open_rebase(None)?;
+            commit_rebase_operation(&mut rebase, &signature)?;
+            return drive_rebase(self, &mut rebase, &signature);
+        }
+
+        Err(GitError::OperationFailed(
+            "No rebase in progress".to_string(),
+        ))
+    }
+
+    #[instrument(skip(self))]
+    fn skip_rebase_step(&self) -> GitResult<RebaseStatus> {
+        info!("Skipping rebase step");
+
+        if let Some(mut state) = load_state(self)? {
+            discard_current_step(self)?;
+            // The sequencer's own state file tracks the plan, not CHERRY_PICK_HEAD
+            self.cleanup_state()?;
+            state.next += 1;
+            save_state(self, &state)?;
+            return run_sequencer(self, state);
+        }
+
+        if is_git_rebase(self) {
+            let signature = self.signature()?;
+            let mut rebase = self.open_rebase(None)?;
+            discard_current_step(self)?;
+            return drive_rebase(self, &mut rebase, &signature);
+        }
+
+        Err(GitError::OperationFailed(
+            "No rebase in progress".to_string(),
+        ))
+    }
+
+    #[instrument(skip(self))]
+    fn abort_rebase(&self) -> GitResult<()> {
+        info!("Aborting rebase");
+
+        if let Some(state) = load_state(self)? {
+            let orig_head = self.find_commit(Oid::from_str(&state.orig_head)?)?;
+            match &state.head_name {
+                Some(name) => self.set_head(name)?,
+                None => self.set_head_detached(orig_head.id())?,
+            }
+            self.reset(orig_head.as_object(), ResetType::Hard, None)?;
+            self.cleanup_state()?;
+            fs::remove_file(state_path(self))?;
+            info!("Interactive rebase aborted");
+            return Ok(());
+        }
+
+        if is_git_rebase(self) {
+            self.open_rebase(None)?.abort()?;
+            info!("Rebase aborted");
+            return Ok(());
+        }
+
+        Err(GitError::OperationFailed(
+            "No rebase in progress".to_string(),
+        ))
+    }
+
+    #[instrument(skip(self))]
+    fn get_rebase_status(&self) -> GitResult<RebaseStatus> {
+        let conflicts = conflicted_paths(&self.index()?)?;
+
+        if let Some(state) = load_state(self)? {
+            return Ok(RebaseStatus {
+                in_progress: true,
+                current_step: Some(state.next),
+                total_steps: state.steps.len(),
+                conflicts,
+                head_sha: head_sha(self),
+            });
+        }
+
+        if is_git_rebase(self) {
+            let mut rebase = self.open_rebase(None)?;
+            return Ok(RebaseStatus {
+                in_progress: true,
+                current_step: rebase.operation_current(),
+                total_steps: rebase.len(),
+                conflicts,
+                head_sha: head_sha(self),
+            });
+        }
+
+        Ok(RebaseStatus {
+            in_progress: false,
+            current_step: None,
+            total_steps: 0,
+            conflicts: Vec::new(),
+            head_sha: head_sha(self),
+        })
+    }
+}
+
+/// Apply the remaining operations of a `git2::Rebase`, stopping at the first
+/// conflicting one and finishing the rebase once every operation is committed.
+pub(crate) fn drive_rebase(
+    repo: &Git2Repository,
+    rebase: &mut Rebase,
+    signature: &Signature,
+) -> GitResult<RebaseStatus> {
+    while let Some(operation) = rebase.next() {
+        let operation = operation?;
+
+        let index = repo.index()?;
+        if index.has_conflicts() {
+            let conflicts = conflicted_paths(&index)?;
+            warn!(commit = %operation.id(), count = conflicts.len(), "Rebase stopped with conflicts");
+            return Ok(RebaseStatus {
+                in_progress: true,
+                current_step: rebase.operation_current(),
+                total_steps: rebase.len(),
+                conflicts,
+                head_sha: head_sha(repo),
+            });
+        }
+
+        commit_rebase_operation(rebase, signature)?;
+    }
+
+    rebase.finish(Some(signature))?;
+
+    info!("Rebase completed");
+    Ok(RebaseStatus {
+        in_progress: false,
+        current_step: None,
+        total_steps: rebase.len(),
+        conflicts: Vec::new(),
+        head_sha: head_sha(repo),
+    })
+}
+
+// Helper functions
+
+fn commit_rebase_operation(rebase: &mut Rebase, signature: &Signature) -> GitResult<()> {
+    match rebase.commit(None, signature, None) {
+        Ok(_) => Ok(()),
+        // The change is already upstream, so there is nothing to commit
+        Err(e) if e.code() == ErrorCode::Applied => {
+            debug!("Skipping already applied commit");
+            Ok(())
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+fn run_sequencer(repo: &Git2Repository, mut state: SequencerState) -> GitResult<RebaseStatus> {
+    let signature = repo.signature()?;
+
+    while state.next < state.steps.len() {
+        let step = state.steps[state.next].clone();
+        if matches!(step.action, RebaseAction::Drop) {
+            debug!(sha = %step.sha, "Dropping commit");
+            state.next += 1;
+            continue;
+        }
+
+        let commit = repo.find_commit(Oid::from_str(&step.sha)?)?;
+
+        // With every earlier step skipped or empty, a squash would amend `onto` itself
+        if matches!(step.action, RebaseAction::Squash | RebaseAction::Fixup)
+            && repo.head()?.peel_to_commit()?.id().to_string() == state.onto
+        {
+            save_state(repo, &state)?;
+            warn!(sha = %step.sha, "Nothing to squash into");
+            return Err(GitError::OperationFailed(format!(
+                "Cannot squash {}: no earlier commit of this rebase to squash into; skip it or abort",
+                step.sha
+            )));
+        }
+
+        let mut checkout = git2::build::CheckoutBuilder::new();
+        checkout
+            .safe()
+            .allow_conflicts(true)
+            .conflict_style_merge(true);
+        let mut opts = git2::CherrypickOptions::new();
+        opts.checkout_builder(checkout);
+        repo.cherrypick(&commit, Some(&mut opts))?;
+
+        let index = repo.index()?;
+        if index.has_conflicts() {
+            save_state(repo, &state)?;
+            let conflicts = conflicted_paths(&index)?;
+            warn!(sha = %step.sha, count = conflicts.len(), "Rebase step stopped with conflicts");
+            return Ok(RebaseStatus {
+                in_progress: true,
+                current_step: Some(state.next),
+                total_steps: state.steps.len(),
+                conflicts,
+                head_sha: head_sha(repo),
+            });
+        }
+
+        commit_step(repo, &step, &commit, &signature)?;
+        state.next += 1;
+        save_state(repo, &state)?;
+    }
+
+    let new_head = repo.head()?.peel_to_commit()?.id();
+    if let Some(name) = &state.head_name {
+        repo.reference(
+            name,
+            new_head,
+            true,
+            &format!("rebase (finish): {} onto {}", name, state.onto),
+        )?;
+        repo.set_head(name)?;
+    }
+    fs::remove_file(state_path(repo))?;
+
+    info!(head = %new_head, "Interactive rebase completed");
+    Ok(RebaseStatus {
+        in_progress: false,
+        current_step: None,
+        total_steps: state.steps.len(),
+        conflicts: Vec::new(),
+        head_sha: Some(new_head.to_string()),
+    })
+}
+
+/// Record the index produced by cherry-picking `commit` according to the step's action
+fn commit_step(
+    repo: &Git2Repository,
+    step: &RebasePlanStep,
+    commit: &Git2Commit,
+    signature: &Signature,
+) -> GitResult<()> {
+    let mut index = repo.index()?;
+    let tree = repo.find_tree(index.write_tree()?)?;
+    let head_commit = repo.head()?.peel_to_commit()?;
+
+    match &step.action {
+        RebaseAction::Squash | RebaseAction::Fixup => {
+            let previous = head_commit.message().unwrap_or("");
+            let message = match step.action {
+                RebaseAction::Squash => format!(
+                    "{}\n\n{}",
+                    previous.trim_end(),
+                    commit.message().unwrap_or("")
+                ),
+                _ => previous.to_string(),
+            };
+            head_commit.amend(
+                Some("HEAD"),
+                None,
+                Some(signature),
+                None,
+                Some(&message),
+                Some(&tree),
+            )?;
+        }
+        RebaseAction::Pick | RebaseAction::Reword { .. } => {
+            if tree.id() == head_commit.tree_id() {
+                debug!(sha = %step.sha, "Commit became empty, skipping");
+            } else {
+                let message = match &step.action {
+                    RebaseAction::Reword { message } => message.as_str(),
+                    _ => commit.message().unwrap_or(""),
+                };
+                repo.commit(
+                    Some("HEAD"),
+                    &commit.author(),
+                    signature,
+                    message,
+                    &tree,
+                    &[&head_commit],
+                )?;
+            }
+        }
+        RebaseAction::Drop => {}
+    }
+
+    repo.cleanup_state()?;
+    Ok(())
+}
+
+fn validate_plan(
+    repo: &Git2Repository,
+    onto: Oid,
+    plan: Vec<RebasePlanStep>,
+) -> GitResult<Vec<RebasePlanStep>> {
+    // Only commits the rebase would replay, `onto..HEAD`, may appear in the plan
+    let mut revwalk = repo.revwalk()?;
+    revwalk.push_head()?;
+    revwalk.hide(onto)?;
+    let replayed = revwalk.collect::<Result<HashSet<Oid>, _>>()?;
+
+    let mut steps = Vec::with_capacity(plan.len());
+    let mut has_base = false;
+
+    for step in plan {
+        let commit = repo.revparse_single(&step.sha)?.peel_to_commit()?;
+        if !replayed.contains(&commit.id()) {
+            return Err(GitError::OperationFailed(format!(
+                "Commit {} is not between the rebase target and HEAD",
+                step.sha
+            )));
+        }
+        if commit.parent_count() > 1 {
+            return Err(GitError::OperationFailed(format!(
+                "Merge commit {} cannot be part of an interactive rebase",
+                step.sha
+            )));
+        }
+
+        match step.action {
+            RebaseAction::Squash | RebaseAction::Fixup if !has_base => {
+                return Err(GitError::OperationFailed(
+                    "The first commit in a rebase plan cannot be squashed".to_string(),
+                ));
+            }
+            RebaseAction::Drop => {}
+            _ => has_base = true,
+        }
+
+        steps.push(RebasePlanStep {
+            sha: commit.id().to_string(),
+            action: step.action,
+        });
+    }
+
+    Ok(steps)
+}
+
+/// Put the working tree and index back to HEAD. Unlike a hard reset this
+/// leaves the rebase state directory in place, so the rebase can go on.
+fn discard_current_step(repo: &Git2Repository) -> GitResult<()> {
+    let head = repo.head()?.peel_to_commit()?;
+    repo.checkout_head(Some(git2::build::CheckoutBuilder::new().force()))?;
+
+    let mut index = repo.index()?;
+    index.read_tree(&head.tree()?)?;
+    index.write()?;
+    Ok(())
+}
+
+fn ensure_no_conflicts(repo: &Git2Repository) -> GitResult<()> {
+    let index = repo.index()?;
+    if index.has_conflicts() {
+        let conflicts = conflicted_paths(&index)?;
+        return Err(GitError::MergeConflict(conflicts.join(", ")));
+    }
+    Ok(())
+}
+
+fn is_git_rebase(repo: &Git2Repository) -> bool {
+    matches!(
+        repo.state(),
+        RepositoryState::Rebase | RepositoryState::RebaseInteractive | RepositoryState::RebaseMerge
+    )
+}
+
+fn head_sha(repo: &Git2Repository) -> Option<String> {
+    repo.head()
+        .ok()
+        .and_then(|head| head.target())
+        .map(|oid| oid.to_string())
+}
+
+fn state_path(repo: &Git2Repository) -> PathBuf {
+    repo.path().join(STATE_FILE)
+}
+
+fn load_state(repo: &Git2Repository) -> GitResult<Option<SequencerState>> {
+    let path = state_path(repo);
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let content = fs::read_to_string(path)?;
+    let state = serde_json::from_str(&content).map_err(|e| {
+        GitError::OperationFailed(format!("Invalid interactive rebase state: {}", e))
+    })?;
+    Ok(Some(state))
+}
+
+fn save_state(repo: &Git2Repository, state: &SequencerState) -> GitResult<()> {
+    let content = serde_json::to_string(state)
+        .map_err(|e| GitError::OperationFailed(format!("Could not save rebase state: {}", e)))?;
+    fs::write(state_path(repo), content)?;
+    Ok(())
+}
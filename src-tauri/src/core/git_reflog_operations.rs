@@ -0,0 +1,131 @@
+use crate::core::git_error::{dirty_paths, GitError, GitResult};
+use crate::core::git_stash_operations::GitStashOperations;
+use crate::models::git_repository::{Author, ReflogEntry};
+use chrono::{TimeZone, Utc};
+use git2::{Repository as Git2Repository, ResetType};
+use tracing::{debug, info, instrument, warn};
+
+/// Extension trait for GitEngine to handle reflog browsing and recovery
+pub trait GitReflogOperations {
+    fn get_reflog(&self, reference: Option<&str>) -> GitResult<Vec<ReflogEntry>>;
+    fn restore_reflog_entry(
+        &mut self,
+        reference: Option<&str>,
+        index: usize,
+        stash_changes: bool,
+    ) -> GitResult<String>;
+}
+
+impl GitReflogOperations for Git2Repository {
+    #[instrument(skip(self))]
+    fn get_reflog(&self, reference: Option<&str>) -> GitResult<Vec<ReflogEntry>> {
+        info!("Reading reflog");
+        let refname = resolve_refname(self, reference)?;
+        let reflog = self.reflog(&refname)?;
+
+        let entries: Vec<ReflogEntry> = reflog
+            .iter()
+            .enumerate()
+            .map(|(index, entry)| {
+                let committer = entry.committer();
+                ReflogEntry {
+                    index,
+                    old_sha: entry.id_old().to_string(),
+                    new_sha: entry.id_new().to_string(),
+                    message: entry.message().unwrap_or("").to_string(),
+                    committer: Author {
+                        name: committer.name().unwrap_or("Unknown").to_string(),
+                        email: committer
+                            .email()
+                            .unwrap_or("unknown@example.com")
+                            .to_string(),
+                        timestamp: Utc
+                            .timestamp_opt(committer.when().seconds(), 0)
+                            .single()
+                            .unwrap_or_else(Utc::now),
+                    },
+                }
+            })
+            .collect();
+
+        debug!(refname, count = entries.len(), "Reflog retrieved");
+        Ok(entries)
+    }
+
+    /// Move HEAD or a branch back to the commit recorded in a reflog entry.
+    ///
+    /// When the restore touches the working tree and it has uncommitted changes,
+    /// this fails with `DirtyWorkingTree` unless `stash_changes` is set, in which
+    /// case the changes are stashed first.
+    #[instrument(skip(self))]
+    fn restore_reflog_entry(
+        &mut self,
+        reference: Option<&str>,
+        index: usize,
+        stash_changes: bool,
+    ) -> GitResult<String> {
+        info!("Restoring reflog entry");
+        let refname = resolve_refname(self, reference)?;
+
+        let target = {
+            let reflog = self.reflog(&refname)?;
+            let entry = reflog.get(index).ok_or_else(|| {
+                GitError::OperationFailed(format!("No reflog entry {}@{{{}}}", refname, index))
+            })?;
+            entry.id_new()
+        };
+
+        // A branch that is not checked out can be moved without touching the working tree
+        let head_refname = self.head().ok().and_then(|h| h.name().map(String::from));
+        let touches_workdir = refname == "HEAD" || head_refname.as_deref() == Some(&refname);
+
+        if !touches_workdir {
+            let mut branch_ref = self.find_reference(&refname)?;
+            branch_ref.set_target(
+                target,
+                &format!("navin: restore to {}@{{{}}}", refname, index),
+            )?;
+            info!(target = %target, "Branch restored from reflog");
+            return Ok(target.to_string());
+        }
+
+        let dirty = dirty_paths(self)?;
+        if !dirty.is_empty() {
+            if !stash_changes {
+                warn!(
+                    count = dirty.len(),
+                    "Refusing to restore over uncommitted changes"
+                );
+                return Err(GitError::DirtyWorkingTree(dirty.join(", ")));
+            }
+            let message = format!(
+                "navin: auto-stash before restoring {}@{{{}}}",
+                refname, index
+            );
+            self.create_stash(Some(&message))?;
+        }
+
+        let commit = self.find_commit(target)?;
+        self.reset(commit.as_object(), ResetType::Hard, None)?;
+
+        info!(target = %target, "HEAD restored from reflog");
+        Ok(target.to_string())
+    }
+}
+
+// Helper functions
+
+fn resolve_refname(repo: &Git2Repository, reference: Option<&str>) -> GitResult<String> {
+    match reference {
+        None | Some("HEAD") => Ok("HEAD".to_string()),
+        Some(name) => {
+            let resolved = repo
+                .resolve_reference_from_short_name(name)
+                .map_err(|_| GitError::BranchNotFound(name.to_string()))?;
+            Ok(resolved
+                .name()
+                .ok_or_else(|| GitError::BranchNotFound(name.to_string()))?
+                .to_string())
+        }
+    }
+}
@@ -1,16 +1,39 @@
-use crate::core::git_error::GitResult;
-use crate::models::git_repository::Remote;
-use git2::Repository as Git2Repository;
-use tracing::{info, instrument};
+use crate::core::git_credentials::{credential_callbacks, map_remote_error};
+use crate::core::git_error::{GitError, GitResult};
+use crate::core::git_merge_operations::merge_annotated_commit;
+use crate::core::git_progress::OperationProgress;
+use crate::core::git_rebase_operations::drive_rebase;
+use crate::models::git_repository::{MergeMode, MergeResult, PullStrategy, Remote, TransferStage};
+use git2::{AnnotatedCommit, Repository as Git2Repository};
+use std::cell::RefCell;
+use tracing::{debug, info, instrument, warn};
 
 /// Extension trait for GitEngine to handle remote operations
 pub trait GitRemoteOperations {
     fn list_remotes(&self) -> GitResult<Vec<Remote>>;
     fn add_remote(&self, name: &str, url: &str) -> GitResult<()>;
     fn remove_remote(&self, name: &str) -> GitResult<()>;
-    fn fetch(&self, remote_name: &str) -> GitResult<()>;
-    fn push(&self, remote_name: &str, branch: &str, force: bool) -> GitResult<()>;
-    fn pull(&self, remote_name: &str, branch: &str) -> GitResult<()>;
+    fn fetch(&self, remote_name: &str, progress: Option<&OperationProgress>) -> GitResult<()>;
+    fn push(
+        &self,
+        remote_name: &str,
+        branch: &str,
+        force: bool,
+        progress: Option<&OperationProgress>,
+    ) -> GitResult<()>;
+    fn pull(
+        &self,
+        remote_name: &str,
+        branch: &str,
+        strategy: PullStrategy,
+        progress: Option<&OperationProgress>,
+    ) -> GitResult<MergeResult>;
+    fn integrate_upstream(
+        &self,
+        remote_name: &str,
+        branch: &str,
+        strategy: PullStrategy,
+    ) -> GitResult<MergeResult>;
 }
 
 impl GitRemoteOperations for Git2Repository {
@@ -55,37 +78,64 @@ impl GitRemoteOperations for Git2Repository {
         Ok(())
     }
 
-    #[instrument(skip(self))]
-    fn fetch(&self, remote_name: &str) -> GitResult<()> {
+    #[instrument(skip(self, progress))]
+    fn fetch(&self, remote_name: &str, progress: Option<&OperationProgress>) -> GitResult<()> {
         info!(remote_name, "Fetching from remote");
         let mut remote = self.find_remote(remote_name)?;
+        let url = remote.url().unwrap_or(remote_name).to_string();
 
-        let mut fetch_options = git2::FetchOptions::new();
-        let mut callbacks = git2::RemoteCallbacks::new();
-
-        callbacks.credentials(|_url, username_from_url, _allowed_types| {
-            git2::Cred::ssh_key_from_agent(username_from_url.unwrap_or("git"))
-        });
+        let mut callbacks = credential_callbacks(self);
+        if let Some(progress) = progress {
+            progress.attach(&mut callbacks);
+        }
 
+        let mut fetch_options = git2::FetchOptions::new();
         fetch_options.remote_callbacks(callbacks);
 
-        remote.fetch(&[] as &[&str], Some(&mut fetch_options), None)?;
+        remote
+            .fetch(&[] as &[&str], Some(&mut fetch_options), None)
+            .map_err(|e| map_remote_error(e, &url, progress))?;
         info!("Fetch completed successfully");
         Ok(())
     }
 
-    #[instrument(skip(self))]
-    fn push(&self, remote_name: &str, branch: &str, force: bool) -> GitResult<()> {
+    #[instrument(skip(self, progress))]
+    fn push(
+        &self,
+        remote_name: &str,
+        branch: &str,
+        force: bool,
+        progress: Option<&OperationProgress>,
+    ) -> GitResult<()> {
         info!(remote_name, branch, force, "Pushing to remote");
         let mut remote = self.find_remote(remote_name)?;
+        let url = remote
+            .pushurl()
+            .or(remote.url())
+            .unwrap_or(remote_name)
+            .to_string();
 
-        let mut push_options = git2::PushOptions::new();
-        let mut callbacks = git2::RemoteCallbacks::new();
+        // The remote reports refused updates per reference instead of failing the push
+        let rejected = RefCell::new(Vec::new());
 
-        callbacks.credentials(|_url, username_from_url, _allowed_types| {
-            git2::Cred::ssh_key_from_agent(username_from_url.unwrap_or("git"))
+        let mut callbacks = credential_callbacks(self);
+        if let Some(progress) = progress {
+            progress.attach(&mut callbacks);
+        }
+        callbacks.push_update_reference(|refname, status| {
+            if let Some(progress) = progress {
+                let message = format!("{}: {}", refname, status.unwrap_or("ok"));
+                progress.emit(TransferStage::UpdatingRef, 0, 0, 0, Some(message));
+            }
+            if let Some(status) = status {
+                rejected
+                    .borrow_mut()
+                    .push(format!("{} ({})", refname, status));
+            }
+            Ok(())
         });
 
+        let mut push_options = git2::PushOptions::new();
         push_options.remote_callbacks(callbacks);
 
         let refspec = if force {
@@ -94,40 +144,156 @@ impl GitRemoteOperations for Git2Repository {
             format!("refs/heads/{}:refs/heads/{}", branch, branch)
         };
 
-        remote.push(&[&refspec], Some(&mut push_options))?;
+        remote
+            .push(&[&refspec], Some(&mut push_options))
+            .map_err(|e| map_remote_error(e, &url, progress))?;
+        drop(push_options);
+
+        let rejected = rejected.into_inner();
+        if !rejected.is_empty() {
+            warn!(count = rejected.len(), "Remote rejected references");
+            return Err(GitError::OperationFailed(format!(
+                "Remote rejected {}",
+                rejected.join(", ")
+            )));
+        }
         info!("Push completed successfully");
         Ok(())
     }
 
+    #[instrument(skip(self, progress))]
+    fn pull(
+        &self,
+        remote_name: &str,
+        branch: &str,
+        strategy: PullStrategy,
+        progress: Option<&OperationProgress>,
+    ) -> GitResult<MergeResult> {
+        info!(remote_name, branch, ?strategy, "Pulling from remote");
+        ensure_checked_out(self, branch)?;
+
+        self.fetch(remote_name, progress)?;
+        let result = self.integrate_upstream(remote_name, branch, strategy)?;
+
+        info!(success = result.success, "Pull completed");
+        Ok(result)
+    }
+
+    /// The second half of a pull: merge or rebase the already fetched upstream
+    /// of `branch` into it
     #[instrument(skip(self))]
-    fn pull(&self, remote_name: &str, branch: &str) -> GitResult<()> {
-        info!(remote_name, branch, "Pulling from remote");
-
-        // First fetch
-        self.fetch(remote_name)?;
-
-        // Then merge
-        let fetch_head = self.find_reference("FETCH_HEAD")?;
-        let fetch_commit = self.reference_to_annotated_commit(&fetch_head)?;
-
-        // Do a merge
-        let analysis = self.merge_analysis(&[&fetch_commit])?;
-
-        if analysis.0.is_fast_forward() {
-            info!("Fast-forward merge");
-            let refname = format!("refs/heads/{}", branch);
-            let mut reference = self.find_reference(&refname)?;
-            reference.set_target(fetch_commit.id(), "Fast-forward")?;
-            self.set_head(&refname)?;
-            self.checkout_head(Some(git2::build::CheckoutBuilder::default().force()))?;
-        } else if analysis.0.is_normal() {
-            info!("Normal merge");
-            self.merge(&[&fetch_commit], None, None)?;
-        } else {
-            info!("Already up to date");
+    fn integrate_upstream(
+        &self,
+        remote_name: &str,
+        branch: &str,
+        strategy: PullStrategy,
+    ) -> GitResult<MergeResult> {
+        ensure_checked_out(self, branch)?;
+
+        let upstream = upstream_reference(self, remote_name, branch)?;
+        let upstream_name = upstream.shorthand().unwrap_or(branch).to_string();
+        let upstream_commit = self.reference_to_annotated_commit(&upstream)?;
+        debug!(upstream = %upstream_name, "Resolved upstream");
+
+        let result = match strategy {
+            PullStrategy::Merge => {
+                let message = format!("Merge remote-tracking branch '{}'", upstream_name);
+                merge_annotated_commit(
+                    self,
+                    &upstream_commit,
+                    MergeMode::FastForward,
+                    None,
+                    &message,
+                )?
+            }
+            PullStrategy::FastForwardOnly => merge_annotated_commit(
+                self,
+                &upstream_commit,
+                MergeMode::FastForwardOnly,
+                None,
+                "",
+            )?,
+            PullStrategy::Rebase => rebase_onto_upstream(self, &upstream_commit)?,
+        };
+
+        debug!(success = result.success, "Upstream integrated");
+        Ok(result)
+    }
+}
+
+// Helper functions
+
+fn ensure_checked_out(repo: &Git2Repository, branch: &str) -> GitResult<()> {
+    let refname = format!("refs/heads/{}", branch);
+    let head_refname = repo.head().ok().and_then(|h| h.name().map(String::from));
+    if head_refname.as_deref() != Some(refname.as_str()) {
+        return Err(GitError::OperationFailed(format!(
+            "Branch '{}' must be checked out to pull into it",
+            branch
+        )));
+    }
+    Ok(())
+}
+
+/// Resolve the remote-tracking reference to integrate: the branch's configured
+/// upstream when it lives on `remote_name`, otherwise `<remote_name>/<branch>`.
+fn upstream_reference<'r>(
+    repo: &'r Git2Repository,
+    remote_name: &str,
+    branch: &str,
+) -> GitResult<git2::Reference<'r>> {
+    let refname = format!("refs/heads/{}", branch);
+    let configured_remote = repo
+        .branch_upstream_remote(&refname)
+        .ok()
+        .and_then(|buf| buf.as_str().map(String::from));
+
+    if configured_remote.as_deref() == Some(remote_name) {
+        if let Ok(upstream) = repo
+            .find_branch(branch, git2::BranchType::Local)
+            .and_then(|b| b.upstream())
+        {
+            return Ok(upstream.into_reference());
         }
+    }
 
-        info!("Pull completed successfully");
-        Ok(())
+    repo.find_reference(&format!("refs/remotes/{}/{}", remote_name, branch))
+        .map_err(|_| GitError::BranchNotFound(format!("{}/{}", remote_name, branch)))
+}
+
+/// Replay local commits onto the fetched upstream. A conflicting step leaves the
+/// rebase in progress and reports the conflicted paths.
+fn rebase_onto_upstream(
+    repo: &Git2Repository,
+    upstream: &AnnotatedCommit,
+) -> GitResult<MergeResult> {
+    let (analysis, _) = repo.merge_analysis(&[upstream])?;
+    if analysis.is_up_to_date() || analysis.is_fast_forward() {
+        // Nothing local to replay
+        return merge_annotated_commit(repo, upstream, MergeMode::FastForward, None, "");
     }
+
+    let head = repo.reference_to_annotated_commit(&repo.head()?)?;
+    let signature = repo.signature()?;
+    let mut rebase = repo.rebase(Some(&head), Some(upstream), None, None)?;
+
+    let status = drive_rebase(repo, &mut rebase, &signature)?;
+    if status.in_progress {
+        warn!(
+            count = status.conflicts.len(),
+            "Pull rebase stopped with conflicts"
+        );
+    }
+
+    Ok(MergeResult {
+        success: !status.in_progress,
+        conflicts: status.conflicts,
+        merged_commit: if status.in_progress {
+            None
+        } else {
+            status.head_sha
+        },
+        unapplied: Vec::new(),
+        squash_pending: false,
+    })
 }
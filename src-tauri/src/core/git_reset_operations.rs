@@ -0,0 +1,81 @@
+use crate::core::git_error::{dirty_paths, GitResult};
+use crate::core::git_stash_operations::GitStashOperations;
+use crate::models::git_repository::{ResetMode, ResetResult};
+use chrono::Utc;
+use git2::{Repository as Git2Repository, ResetType};
+use tracing::{debug, info, instrument};
+
+/// Namespace for recovery points recorded before destructive resets
+pub const BACKUP_REF_PREFIX: &str = "refs/navin/backup";
+
+/// Extension trait for GitEngine to handle resets
+pub trait GitResetOperations {
+    fn reset_to(&mut self, target: &str, mode: ResetMode) -> GitResult<ResetResult>;
+}
+
+impl GitResetOperations for Git2Repository {
+    #[instrument(skip(self))]
+    fn reset_to(&mut self, target: &str, mode: ResetMode) -> GitResult<ResetResult> {
+        info!("Resetting to revision");
+        let target_oid = self.revparse_single(target)?.peel_to_commit()?.id();
+
+        let mut backup_ref = None;
+        let mut stash_sha = None;
+
+        if matches!(mode, ResetMode::Hard) {
+            let (refname, stash) = backup_before_hard_reset(self, &format!("reset to {}", target))?;
+            backup_ref = Some(refname);
+            stash_sha = stash;
+        }
+
+        let reset_type = match mode {
+            ResetMode::Soft => ResetType::Soft,
+            ResetMode::Mixed => ResetType::Mixed,
+            ResetMode::Hard => ResetType::Hard,
+        };
+
+        let commit = self.find_commit(target_oid)?;
+        self.reset(commit.as_object(), reset_type, None)?;
+
+        info!(head = %target_oid, "Reset completed successfully");
+        Ok(ResetResult {
+            head_sha: target_oid.to_string(),
+            backup_ref,
+            stash_sha,
+        })
+    }
+}
+
+// Helper functions
+
+/// Keep the current commit reachable under `BACKUP_REF_PREFIX` and stash any
+/// uncommitted changes, so a hard reset can be undone. Returns the backup ref
+/// and the stash, if one was made.
+pub(crate) fn backup_before_hard_reset(
+    repo: &mut Git2Repository,
+    reason: &str,
+) -> GitResult<(String, Option<String>)> {
+    let head_oid = repo.head()?.peel_to_commit()?.id();
+    let short_sha: String = head_oid.to_string().chars().take(7).collect();
+    let refname = format!(
+        "{}/{}-{}",
+        BACKUP_REF_PREFIX,
+        Utc::now().format("%Y%m%d-%H%M%S"),
+        short_sha
+    );
+    repo.reference(
+        &refname,
+        head_oid,
+        true,
+        &format!("navin: backup before {}", reason),
+    )?;
+    debug!(refname, "Backup reference recorded");
+
+    let stash_sha = if !dirty_paths(repo)?.is_empty() {
+        let message = format!("navin: auto-stash before {}", reason);
+        Some(repo.create_stash(Some(&message))?)
+    } else {
+        None
+    };
+    Ok((refname, stash_sha))
+}
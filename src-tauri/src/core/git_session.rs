@@ -0,0 +1,42 @@
+use crate::core::git_engine::GitEngine;
+use crate::core::git_error::GitResult;
+use std::sync::{Arc, Mutex, MutexGuard};
+use tracing::{debug, warn};
+
+/// An engine kept open for the lifetime of a repository in the app.
+///
+/// Commands share the session instead of reopening the repository, and the
+/// mutex serialises them, as a `git2::Repository` must not be used from two
+/// threads at once. Clones refer to the same engine.
+#[derive(Clone)]
+pub struct GitSession {
+    engine: Arc<Mutex<GitEngine>>,
+}
+
+impl GitSession {
+    pub fn new(engine: GitEngine) -> Self {
+        Self {
+            engine: Arc::new(Mutex::new(engine)),
+        }
+    }
+
+    /// Lock the engine for one operation.
+    ///
+    /// libgit2 caches the index in memory, so it is reloaded from disk first:
+    /// the git CLI or another tool may have changed it, and an earlier operation
+    /// may have failed after changing the cached copy but before writing it.
+    pub fn lock(&self) -> GitResult<MutexGuard<'_, GitEngine>> {
+        let engine = self.engine.lock().unwrap_or_else(|poisoned| {
+            // The forced reload below drops whatever the panicked operation left
+            warn!("Recovering engine after a panicked operation");
+            self.engine.clear_poison();
+            poisoned.into_inner()
+        });
+
+        if !engine.repo.is_bare() {
+            engine.repo.index()?.read(true)?;
+            debug!("Index reloaded");
+        }
+        Ok(engine)
+    }
+}
@@ -0,0 +1,152 @@
+use crate::core::git_credentials::{credential_callbacks, map_remote_error};
+use crate::core::git_error::{GitError, GitResult};
+use crate::models::git_repository::{Author, Tag};
+use chrono::{TimeZone, Utc};
+use git2::{ObjectType, Repository as Git2Repository};
+use tracing::{debug, info, instrument};
+
+/// Extension trait for GitEngine to handle tag operations
+pub trait GitTagOperations {
+    fn list_tags(&self) -> GitResult<Vec<Tag>>;
+    fn create_lightweight_tag(&self, name: &str, target: Option<&str>) -> GitResult<String>;
+    fn create_annotated_tag(
+        &self,
+        name: &str,
+        target: Option<&str>,
+        message: &str,
+    ) -> GitResult<String>;
+    fn delete_tag(&self, name: &str) -> GitResult<()>;
+    fn push_tag(&self, remote_name: &str, name: &str, force: bool) -> GitResult<()>;
+}
+
+impl GitTagOperations for Git2Repository {
+    #[instrument(skip(self))]
+    fn list_tags(&self) -> GitResult<Vec<Tag>> {
+        info!("Listing tags");
+        let tag_names = self.tag_names(None)?;
+        let mut tags = Vec::new();
+
+        for name in tag_names.iter().flatten() {
+            let reference = match self.find_reference(&format!("refs/tags/{}", name)) {
+                Ok(reference) => reference,
+                Err(_) => continue,
+            };
+
+            // Annotated tags point at a tag object, lightweight tags at the commit itself
+            let tag = match reference.peel_to_tag() {
+                Ok(annotated) => Tag {
+                    name: name.to_string(),
+                    target_sha: annotated.target_id().to_string(),
+                    message: annotated.message().map(|m| m.trim_end().to_string()),
+                    tagger: annotated.tagger().map(|s| signature_to_author(&s)),
+                    is_annotated: true,
+                },
+                Err(_) => Tag {
+                    name: name.to_string(),
+                    target_sha: reference.peel(ObjectType::Any)?.id().to_string(),
+                    message: None,
+                    tagger: None,
+                    is_annotated: false,
+                },
+            };
+
+            tags.push(tag);
+        }
+
+        info!(count = tags.len(), "Tags listed");
+        Ok(tags)
+    }
+
+    #[instrument(skip(self))]
+    fn create_lightweight_tag(&self, name: &str, target: Option<&str>) -> GitResult<String> {
+        info!("Creating lightweight tag");
+        let object = resolve_target(self, target)?;
+
+        let oid = self.tag_lightweight(name, &object, false)?;
+        debug!(oid = %oid, "Lightweight tag created");
+        Ok(oid.to_string())
+    }
+
+    #[instrument(skip(self, message), fields(message_len = message.len()))]
+    fn create_annotated_tag(
+        &self,
+        name: &str,
+        target: Option<&str>,
+        message: &str,
+    ) -> GitResult<String> {
+        info!("Creating annotated tag");
+        if message.trim().is_empty() {
+            return Err(GitError::OperationFailed(
+                "Annotated tags require a message".to_string(),
+            ));
+        }
+
+        let object = resolve_target(self, target)?;
+        let tagger = self.signature()?;
+
+        let oid = self.tag(name, &object, &tagger, message, false)?;
+        debug!(oid = %oid, "Annotated tag created");
+        Ok(oid.to_string())
+    }
+
+    #[instrument(skip(self))]
+    fn delete_tag(&self, name: &str) -> GitResult<()> {
+        info!("Deleting tag");
+        self.tag_delete(name)?;
+        info!("Tag deleted successfully");
+        Ok(())
+    }
+
+    #[instrument(skip(self))]
+    fn push_tag(&self, remote_name: &str, name: &str, force: bool) -> GitResult<()> {
+        info!("Pushing tag to remote");
+        let mut remote = self.find_remote(remote_name)?;
+        let url = remote
+            .pushurl()
+            .or(remote.url())
+            .unwrap_or(remote_name)
+            .to_string();
+
+        let mut push_options = git2::PushOptions::new();
+        push_options.remote_callbacks(credential_callbacks(self));
+
+        let refspec = if force {
+            format!("+refs/tags/{}:refs/tags/{}", name, name)
+        } else {
+            format!("refs/tags/{}:refs/tags/{}", name, name)
+        };
+
+        remote
+            .push(&[&refspec], Some(&mut push_options))
+            .map_err(|e| map_remote_error(e, &url, None))?;
+        info!("Tag pushed successfully");
+        Ok(())
+    }
+}
+
+// Helper functions
+
+fn resolve_target<'r>(
+    repo: &'r Git2Repository,
+    target: Option<&str>,
+) -> GitResult<git2::Object<'r>> {
+    let object = match target {
+        Some(spec) => repo.revparse_single(spec)?,
+        None => repo.head()?.peel(ObjectType::Commit)?,
+    };
+    Ok(object)
+}
+
+fn signature_to_author(signature: &git2::Signature) -> Author {
+    Author {
+        name: signature.name().unwrap_or("Unknown").to_string(),
+        email: signature
+            .email()
+            .unwrap_or("unknown@example.com")
+            .to_string(),
+        timestamp: Utc
+            .timestamp_opt(signature.when().seconds(), 0)
+            .single()
+            .unwrap_or_else(Utc::now),
+    }
+}
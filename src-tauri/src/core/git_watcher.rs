@@ -0,0 +1,308 @@
+use crate::core::git_error::{GitError, GitResult};
+use crate::core::git_worktree_operations::common_dir;
+use crate::models::git_repository::{RepositoryChangeEvent, RepositoryChangeKind};
+use git2::Repository as Git2Repository;
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::sync::{Arc, Mutex, Weak};
+use std::thread;
+use std::time::{Duration, Instant};
+use tracing::{debug, info, instrument, warn};
+
+/// Quiet period after the last change before events are emitted
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Longest a burst of changes can delay its events
+const MAX_DELAY: Duration = Duration::from_secs(2);
+
+/// Most paths listed in a single event
+const MAX_REPORTED_PATHS: usize = 100;
+
+/// Receiver for debounced repository change events
+pub type ChangeSink = Arc<dyn Fn(RepositoryChangeEvent) + Send + Sync>;
+
+/// Watches a repository's working tree and git directory in the background.
+///
+/// Filesystem events are classified on a worker thread: gitignored paths are
+/// dropped, `HEAD`, `index` and refs are reported as their own kinds, and each
+/// burst is reported once it has been quiet for a moment. Dropping the watcher
+/// stops it.
+///
+/// Working tree directories are watched one by one so that ignored ones such
+/// as `target` or `node_modules` never cost an inotify watch; directories
+/// created later are added as they appear.
+pub struct RepositoryWatcher {
+    _watcher: Arc<Mutex<RecommendedWatcher>>,
+}
+
+impl RepositoryWatcher {
+    /// Start watching; events carry `repository` so the receiver can tell
+    /// repositories apart
+    #[instrument(skip(path, sink), fields(path = %path.as_ref().display()))]
+    pub fn start<P: AsRef<Path>>(path: P, repository: &str, sink: ChangeSink) -> GitResult<Self> {
+        info!("Starting repository watcher");
+        let path = path.as_ref();
+        let classifier = ChangeClassifier::new(Git2Repository::open(path)?);
+
+        let (sender, receiver) = mpsc::channel();
+        let mut watcher = notify::recommended_watcher(move |result: notify::Result<Event>| {
+            match result {
+                Ok(event) => {
+                    // The receiver is gone once the watcher is being dropped
+                    let _ = sender.send(event);
+                }
+                Err(e) => warn!("Filesystem watcher error: {}", e),
+            }
+        })
+        .map_err(|e| watch_error(path, e))?;
+
+        for root in classifier.git_roots() {
+            debug!(root = %root.display(), "Watching git directory");
+            watcher
+                .watch(&root, RecursiveMode::Recursive)
+                .map_err(|e| watch_error(&root, e))?;
+        }
+        if let Some(workdir) = &classifier.workdir {
+            classifier.watch_tree(&mut watcher, workdir)?;
+        }
+
+        // The worker only holds a weak handle, so dropping this watcher still
+        // disconnects the channel and ends the thread
+        let watcher = Arc::new(Mutex::new(watcher));
+        let handle = Arc::downgrade(&watcher);
+        let repository = repository.to_string();
+        thread::Builder::new()
+            .name("repository-watcher".to_string())
+            .spawn(move || debounce_changes(receiver, classifier, handle, repository, sink))?;
+
+        info!("Repository watcher started");
+        Ok(Self { _watcher: watcher })
+    }
+}
+
+// Helper functions
+
+fn watch_error(path: &Path, error: notify::Error) -> GitError {
+    GitError::OperationFailed(format!("Could not watch {}: {}", path.display(), error))
+}
+
+/// Collect classified changes and emit them once the filesystem goes quiet
+fn debounce_changes(
+    receiver: Receiver<Event>,
+    classifier: ChangeClassifier,
+    watcher: Weak<Mutex<RecommendedWatcher>>,
+    repository: String,
+    sink: ChangeSink,
+) {
+    let mut pending = PendingChanges::default();
+    let mut first_change: Option<Instant> = None;
+
+    loop {
+        let received = match first_change {
+            None => receiver.recv().map_err(|_| RecvTimeoutError::Disconnected),
+            Some(first) => match MAX_DELAY.saturating_sub(first.elapsed()) {
+                Duration::ZERO => Err(RecvTimeoutError::Timeout),
+                remaining => receiver.recv_timeout(DEBOUNCE.min(remaining)),
+            },
+        };
+
+        match received {
+            Ok(event) => {
+                if matches!(event.kind, EventKind::Access(_)) {
+                    continue;
+                }
+                if matches!(event.kind, EventKind::Create(_)) {
+                    watch_new_directories(&classifier, &watcher, &event.paths);
+                }
+                for path in &event.paths {
+                    if let Some((kind, relative)) = classifier.classify(path) {
+                        pending.add(kind, relative);
+                        first_change.get_or_insert_with(Instant::now);
+                    }
+                }
+            }
+            Err(RecvTimeoutError::Timeout) => {
+                first_change = None;
+                let changes = std::mem::take(&mut pending);
+                changes.emit(&classifier, &repository, &sink);
+            }
+            Err(RecvTimeoutError::Disconnected) => {
+                debug!("Repository watcher stopped");
+                break;
+            }
+        }
+    }
+}
+
+/// Start watching directories created inside the working tree
+fn watch_new_directories(
+    classifier: &ChangeClassifier,
+    watcher: &Weak<Mutex<RecommendedWatcher>>,
+    paths: &[PathBuf],
+) {
+    let Some(watcher) = watcher.upgrade() else {
+        return;
+    };
+    for path in paths {
+        if !fs::symlink_metadata(path).is_ok_and(|m| m.is_dir()) {
+            continue;
+        }
+        let mut watcher = watcher
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if let Err(e) = classifier.watch_tree(&mut watcher, path) {
+            warn!("Could not watch new directory {}: {}", path.display(), e);
+        }
+    }
+}
+
+#[derive(Default)]
+struct PendingChanges {
+    status: BTreeSet<String>,
+    head: BTreeSet<String>,
+    refs: BTreeSet<String>,
+}
+
+impl PendingChanges {
+    fn add(&mut self, kind: RepositoryChangeKind, path: String) {
+        let paths = match kind {
+            RepositoryChangeKind::Status => &mut self.status,
+            RepositoryChangeKind::Head => &mut self.head,
+            RepositoryChangeKind::Refs => &mut self.refs,
+        };
+        if paths.len() < MAX_REPORTED_PATHS {
+            paths.insert(path);
+        }
+    }
+
+    fn emit(mut self, classifier: &ChangeClassifier, repository: &str, sink: &ChangeSink) {
+        // A commit only rewrites the branch HEAD points to, yet HEAD has moved
+        if let Some(head_ref) = classifier.head_ref() {
+            if self.refs.contains(&head_ref) || self.refs.contains("packed-refs") {
+                self.head.insert(head_ref);
+            }
+        }
+        // Staged changes are relative to HEAD, so they change with it
+        if !self.head.is_empty() {
+            self.status.insert("HEAD".to_string());
+        }
+
+        for (kind, paths) in [
+            (RepositoryChangeKind::Head, self.head),
+            (RepositoryChangeKind::Refs, self.refs),
+            (RepositoryChangeKind::Status, self.status),
+        ] {
+            if !paths.is_empty() {
+                debug!(?kind, count = paths.len(), "Repository changed");
+                sink(RepositoryChangeEvent {
+                    repository: repository.to_string(),
+                    kind,
+                    paths: paths.into_iter().collect(),
+                });
+            }
+        }
+    }
+}
+
+/// Decides what a changed path means for the repository
+struct ChangeClassifier {
+    repo: Git2Repository,
+    workdir: Option<PathBuf>,
+    git_dir: PathBuf,
+    common_dir: PathBuf,
+}
+
+impl ChangeClassifier {
+    fn new(repo: Git2Repository) -> Self {
+        // Event paths are canonical, so compare against canonical roots
+        let canonical = |path: &Path| path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+        Self {
+            workdir: repo.workdir().map(canonical),
+            git_dir: canonical(repo.path()),
+            common_dir: canonical(&common_dir(repo.path())),
+            repo,
+        }
+    }
+
+    /// Git directories to watch recursively, leaving out one inside the other
+    fn git_roots(&self) -> Vec<PathBuf> {
+        let mut roots: Vec<PathBuf> = Vec::new();
+        for candidate in [&self.git_dir, &self.common_dir] {
+            if !roots.iter().any(|root| candidate.starts_with(root)) {
+                roots.retain(|root| !root.starts_with(candidate));
+                roots.push(candidate.clone());
+            }
+        }
+        roots
+    }
+
+    /// Watch `dir` and every directory below it that is neither ignored nor
+    /// part of the git directory, which has its own recursive watch
+    fn watch_tree(&self, watcher: &mut RecommendedWatcher, dir: &Path) -> GitResult<()> {
+        let Some(relative) = self.workdir.as_ref().and_then(|w| dir.strip_prefix(w).ok()) else {
+            return Ok(());
+        };
+        let in_git_dir = [&self.git_dir, &self.common_dir]
+            .iter()
+            .any(|git_dir| dir.starts_with(git_dir));
+        let ignored = !relative.as_os_str().is_empty()
+            && self.repo.is_path_ignored(relative).unwrap_or(false);
+        if in_git_dir || ignored {
+            return Ok(());
+        }
+
+        watcher
+            .watch(dir, RecursiveMode::NonRecursive)
+            .map_err(|e| watch_error(dir, e))?;
+        for entry in fs::read_dir(dir)?.flatten() {
+            // Symlinked directories are not followed
+            if entry.file_type().is_ok_and(|t| t.is_dir()) {
+                self.watch_tree(watcher, &entry.path())?;
+            }
+        }
+        Ok(())
+    }
+
+    fn classify(&self, path: &Path) -> Option<(RepositoryChangeKind, String)> {
+        // HEAD and index under the common directory belong to the main working
+        // tree when this is a linked worktree
+        for (git_dir, own) in [(&self.git_dir, true), (&self.common_dir, false)] {
+            if let Ok(relative) = path.strip_prefix(git_dir) {
+                let relative = relative.to_string_lossy().replace('\\', "/");
+                // Lock files are renamed onto their target when git is done
+                if relative.ends_with(".lock") {
+                    return None;
+                }
+                let kind = match relative.as_str() {
+                    "HEAD" if own => RepositoryChangeKind::Head,
+                    "index" if own => RepositoryChangeKind::Status,
+                    "packed-refs" => RepositoryChangeKind::Refs,
+                    r if r.starts_with("refs/") => RepositoryChangeKind::Refs,
+                    _ => return None,
+                };
+                return Some((kind, relative));
+            }
+        }
+
+        let relative = path.strip_prefix(self.workdir.as_ref()?).ok()?;
+        if relative.as_os_str().is_empty() || self.repo.is_path_ignored(relative).unwrap_or(false) {
+            return None;
+        }
+        Some((
+            RepositoryChangeKind::Status,
+            relative.to_string_lossy().replace('\\', "/"),
+        ))
+    }
+
+    /// Ref that HEAD points to, e.g. `refs/heads/main`; `None` when detached
+    fn head_ref(&self) -> Option<String> {
+        self.repo
+            .find_reference("HEAD")
+            .ok()?
+            .symbolic_target()
+            .map(String::from)
+    }
+}
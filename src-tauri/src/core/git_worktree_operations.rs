@@ -0,0 +1,259 @@
+use crate::core::git_error::{GitError, GitResult};
+use crate::models::git_repository::{AddWorktreeOptions, Worktree};
+use git2::{
+    BranchType, Repository as Git2Repository, Worktree as Git2Worktree, WorktreeAddOptions,
+    WorktreeLockStatus,
+};
+use std::path::{Path, PathBuf};
+use tracing::{debug, info, instrument, warn};
+
+/// Extension trait for GitEngine to handle linked worktrees
+pub trait GitWorktreeOperations {
+    fn list_worktrees(&self) -> GitResult<Vec<Worktree>>;
+    fn add_worktree(&self, options: &AddWorktreeOptions) -> GitResult<Worktree>;
+    fn lock_worktree(&self, name: &str, reason: Option<&str>) -> GitResult<()>;
+    fn unlock_worktree(&self, name: &str) -> GitResult<()>;
+    fn prune_worktrees(&self) -> GitResult<Vec<String>>;
+    fn worktree_path(&self, name: &str) -> GitResult<PathBuf>;
+}
+
+impl GitWorktreeOperations for Git2Repository {
+    #[instrument(skip(self))]
+    fn list_worktrees(&self) -> GitResult<Vec<Worktree>> {
+        info!("Listing worktrees");
+        let current = self.workdir().map(canonical);
+        let mut worktrees = Vec::new();
+
+        // Linked worktrees share the main repository's git directory
+        let main = if self.is_worktree() {
+            Some(Git2Repository::open(common_dir(self.path()))?)
+        } else {
+            None
+        };
+        let main = main.as_ref().unwrap_or(self);
+        if let Some(workdir) = main.workdir() {
+            let (branch, head_sha) = head_of(main);
+            worktrees.push(Worktree {
+                name: None,
+                path: path_string(workdir),
+                branch,
+                head_sha,
+                is_main: true,
+                is_current: current.as_deref() == Some(canonical(workdir).as_path()),
+                is_locked: false,
+                lock_reason: None,
+                is_prunable: false,
+            });
+        }
+
+        for name in self.worktrees()?.iter().flatten() {
+            let worktree = self.find_worktree(name)?;
+            let mut entry = worktree_entry(&worktree)?;
+            entry.is_current = current.as_deref() == Some(canonical(worktree.path()).as_path());
+            worktrees.push(entry);
+        }
+
+        info!(count = worktrees.len(), "Worktrees listed");
+        Ok(worktrees)
+    }
+
+    #[instrument(skip(self, options), fields(path = %options.path, branch = %options.branch))]
+    fn add_worktree(&self, options: &AddWorktreeOptions) -> GitResult<Worktree> {
+        info!("Adding worktree");
+        let path = resolve_worktree_path(self, &options.path)?;
+        let path = path.as_path();
+        let name = match &options.name {
+            Some(name) => name.clone(),
+            None => path
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .ok_or_else(|| GitError::InvalidPath(options.path.clone()))?,
+        };
+        if path.exists() && path.read_dir()?.next().is_some() {
+            return Err(GitError::InvalidPath(format!(
+                "{} already exists and is not empty",
+                options.path
+            )));
+        }
+
+        let mut branch = if options.create_branch {
+            let start = options.start_point.as_deref().unwrap_or("HEAD");
+            let commit = self
+                .revparse_single(start)
+                .and_then(|object| object.peel_to_commit())
+                .map_err(|e| {
+                    GitError::OperationFailed(format!("Invalid start point '{}': {}", start, e))
+                })?;
+            debug!(start_point = %commit.id(), "Creating branch for worktree");
+            self.branch(&options.branch, &commit, false)?
+        } else {
+            self.find_branch(&options.branch, BranchType::Local)
+                .map_err(|_| GitError::BranchNotFound(options.branch.clone()))?
+        };
+
+        let mut add_options = WorktreeAddOptions::new();
+        add_options.reference(Some(branch.get()));
+        let worktree = match self.worktree(&name, path, Some(&add_options)) {
+            Ok(worktree) => worktree,
+            Err(e) => {
+                // Do not leave behind a branch created only for this worktree
+                if options.create_branch {
+                    if let Err(delete_error) = branch.delete() {
+                        warn!("Could not delete branch after failed add: {}", delete_error);
+                    }
+                }
+                return Err(GitError::OperationFailed(format!(
+                    "Could not add worktree '{}': {}",
+                    name,
+                    e.message()
+                )));
+            }
+        };
+
+        info!(name, "Worktree added");
+        worktree_entry(&worktree)
+    }
+
+    #[instrument(skip(self, reason))]
+    fn lock_worktree(&self, name: &str, reason: Option<&str>) -> GitResult<()> {
+        info!("Locking worktree");
+        let worktree = self.find_worktree(name)?;
+        if let WorktreeLockStatus::Locked(_) = worktree.is_locked()? {
+            return Err(GitError::OperationFailed(format!(
+                "Worktree '{}' is already locked",
+                name
+            )));
+        }
+
+        worktree.lock(reason)?;
+        info!("Worktree locked");
+        Ok(())
+    }
+
+    #[instrument(skip(self))]
+    fn unlock_worktree(&self, name: &str) -> GitResult<()> {
+        info!("Unlocking worktree");
+        let worktree = self.find_worktree(name)?;
+        if let WorktreeLockStatus::Unlocked = worktree.is_locked()? {
+            return Err(GitError::OperationFailed(format!(
+                "Worktree '{}' is not locked",
+                name
+            )));
+        }
+
+        worktree.unlock()?;
+        info!("Worktree unlocked");
+        Ok(())
+    }
+
+    /// Remove the administrative files of worktrees whose directory is gone.
+    /// Locked worktrees are kept, as their directory may be on removable media.
+    #[instrument(skip(self))]
+    fn prune_worktrees(&self) -> GitResult<Vec<String>> {
+        info!("Pruning worktrees");
+        let mut pruned = Vec::new();
+
+        for name in self.worktrees()?.iter().flatten() {
+            let worktree = self.find_worktree(name)?;
+            if worktree.is_prunable(None)? {
+                worktree.prune(None)?;
+                debug!(name, "Worktree pruned");
+                pruned.push(name.to_string());
+            }
+        }
+
+        info!(count = pruned.len(), "Worktrees pruned");
+        Ok(pruned)
+    }
+
+    #[instrument(skip(self))]
+    fn worktree_path(&self, name: &str) -> GitResult<PathBuf> {
+        let worktree = self.find_worktree(name)?;
+        if worktree.validate().is_err() {
+            return Err(GitError::InvalidPath(format!(
+                "Worktree '{}' no longer exists at {}; prune it to remove it",
+                name,
+                worktree.path().display()
+            )));
+        }
+        Ok(worktree.path().to_path_buf())
+    }
+}
+
+// Helper functions
+
+/// Relative paths are taken from the directory holding the main working tree,
+/// so `feature` becomes a sibling of the repository rather than depending on the
+/// app's current directory
+fn resolve_worktree_path(repo: &Git2Repository, path: &str) -> GitResult<PathBuf> {
+    let path = Path::new(path);
+    if path.is_absolute() {
+        return Ok(path.to_path_buf());
+    }
+
+    let main = Git2Repository::open(common_dir(repo.path()))?;
+    let base = main.workdir().and_then(Path::parent).ok_or_else(|| {
+        GitError::InvalidPath(format!(
+            "{} is relative and the repository has no working tree to resolve it from",
+            path.display()
+        ))
+    })?;
+    debug!(base = %base.display(), "Resolving relative worktree path");
+    Ok(base.join(path))
+}
+
+/// Git directory shared by all worktrees; linked worktrees name it in `commondir`
+pub(crate) fn common_dir(git_dir: &Path) -> PathBuf {
+    match std::fs::read_to_string(git_dir.join("commondir")) {
+        Ok(relative) => git_dir.join(relative.trim()),
+        Err(_) => git_dir.to_path_buf(),
+    }
+}
+
+fn worktree_entry(worktree: &Git2Worktree) -> GitResult<Worktree> {
+    let (is_locked, lock_reason) = match worktree.is_locked()? {
+        WorktreeLockStatus::Locked(reason) => (true, reason.filter(|r| !r.is_empty())),
+        WorktreeLockStatus::Unlocked => (false, None),
+    };
+
+    // A worktree whose directory is gone cannot be opened
+    let (branch, head_sha) = match Git2Repository::open_from_worktree(worktree) {
+        Ok(repo) if worktree.validate().is_ok() => head_of(&repo),
+        _ => (None, None),
+    };
+
+    Ok(Worktree {
+        name: worktree.name().map(String::from),
+        path: path_string(worktree.path()),
+        branch,
+        head_sha,
+        is_main: false,
+        is_current: false,
+        is_locked,
+        lock_reason,
+        is_prunable: worktree.is_prunable(None)?,
+    })
+}
+
+fn head_of(repo: &Git2Repository) -> (Option<String>, Option<String>) {
+    match repo.head() {
+        Ok(head) => (
+            head.is_branch()
+                .then(|| head.shorthand().map(String::from))
+                .flatten(),
+            head.target().map(|oid| oid.to_string()),
+        ),
+        Err(_) => (None, None),
+    }
+}
+
+fn canonical(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// libgit2 reports working directories with a trailing separator
+fn path_string(path: &Path) -> String {
+    path.to_string_lossy()
+        .trim_end_matches(['/', '\\'])
+        .to_string()
+}
@@ -4,16 +4,32 @@ pub mod result;
 pub mod validation;
 
 // Git engine modules
+pub mod git_blame_operations;
 pub mod git_branch_ops;
+pub mod git_clone_operations;
+pub mod git_conflict_operations;
+pub mod git_credentials;
 pub mod git_diff_helpers;
 pub mod git_diff_operations;
+pub mod git_discard_operations;
 pub mod git_engine;
 mod git_engine_delegates;
 pub mod git_error;
+pub mod git_graph_layout;
 pub mod git_history_operations;
+pub mod git_hunk_operations;
+pub mod git_merge_operations;
 pub mod git_operations;
+pub mod git_progress;
+pub mod git_rebase_operations;
+pub mod git_reflog_operations;
 pub mod git_remote_operations;
+pub mod git_reset_operations;
+pub mod git_session;
 pub mod git_stash_operations;
+pub mod git_tag_operations;
+pub mod git_watcher;
+pub mod git_worktree_operations;
 
 // Re-export commonly used types
 pub use git_engine::GitEngine;
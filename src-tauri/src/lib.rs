@@ -59,29 +59,62 @@ pub fn run() {
             // Git commands
             open_repository,
             discover_repository,
+            clone_repository,
+            init_repository,
             get_repository_status,
             get_branches,
             stage_file,
             stage_all,
             unstage_file,
             unstage_all,
+            stage_hunk,
+            unstage_hunk,
+            discard_hunk,
+            discard_changes,
+            clean_untracked,
+            restore_discard_backup,
             create_commit,
+            reset_to,
             checkout_branch,
             create_branch,
             delete_branch,
+            merge_branch,
+            get_conflict_versions,
+            resolve_conflict,
+            abort_merge,
+            continue_merge,
+            // Rebase commands
+            get_rebase_todo,
+            start_interactive_rebase,
+            continue_rebase,
+            skip_rebase_step,
+            abort_rebase,
+            get_rebase_status,
             get_current_repository,
+            list_open_repositories,
+            set_active_repository,
+            close_repository,
             get_git_config,
             get_git_config_detailed,
             set_git_config,
             // Phase 2: History commands
             get_commits,
+            get_commit_graph,
+            search_commits,
             get_commit_details,
             get_commit_diff,
             get_file_history,
+            get_file_blame,
+            cherry_pick_commits,
+            revert_commits,
+            // Reflog commands
+            get_reflog,
+            restore_reflog_entry,
             // Phase 2: Diff commands
             get_file_diff_unstaged,
             get_file_diff_staged,
             get_diff_between_commits,
+            get_commit_file_diff,
             // Phase 2: Remote commands  
             list_remotes,
             add_remote,
@@ -89,12 +122,29 @@ pub fn run() {
             fetch_remote,
             push_to_remote,
             pull_from_remote,
+            cancel_operation,
+            // Credential commands
+            save_credential,
+            get_credential_info,
+            delete_credential,
             // Stash commands
             list_stashes,
             create_stash,
             apply_stash,
             pop_stash,
-            drop_stash
+            drop_stash,
+            // Tag commands
+            list_tags,
+            create_tag,
+            delete_tag,
+            push_tag,
+            // Worktree commands
+            list_worktrees,
+            add_worktree,
+            lock_worktree,
+            unlock_worktree,
+            prune_worktrees,
+            open_worktree
         ])
         .manage(GitState::new())
         .setup(|app| {
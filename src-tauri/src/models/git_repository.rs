@@ -1,9 +1,13 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::fmt;
 
 /// Repository information
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RepositoryInfo {
+    /// Registry id, set once the repository is open in the app
+    #[serde(default)]
+    pub id: Option<String>,
     pub path: String,
     pub name: String,
     pub current_branch: Option<String>,
@@ -13,6 +17,16 @@ pub struct RepositoryInfo {
     pub remotes: Vec<String>,
 }
 
+/// Entry of the open repository list
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OpenRepositoryInfo {
+    pub id: String,
+    pub path: String,
+    pub name: String,
+    /// Used by commands that are not given a repository id
+    pub is_active: bool,
+}
+
 /// Repository status summary
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RepositoryStatus {
@@ -20,9 +34,29 @@ pub struct RepositoryStatus {
     pub unstaged: Vec<FileStatus>,
     pub untracked: Vec<FileStatus>,
     pub conflicted: Vec<FileStatus>,
+    /// Only listed when requested; ignored directories are reported as a whole
+    pub ignored: Vec<FileStatus>,
+    pub submodules: Vec<SubmoduleStatus>,
     pub is_clean: bool,
 }
 
+/// State of a submodule relative to the commit its superproject records
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubmoduleStatus {
+    pub name: String,
+    pub path: String,
+    pub url: Option<String>,
+    /// Commit recorded in the superproject's index
+    pub recorded_sha: Option<String>,
+    /// Commit checked out in the submodule
+    pub checked_out_sha: Option<String>,
+    pub initialized: bool,
+    /// The checked out commit differs from the recorded one
+    pub out_of_date: bool,
+    /// The submodule has uncommitted or untracked changes
+    pub dirty: bool,
+}
+
 /// File status information
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FileStatus {
@@ -82,6 +116,94 @@ pub struct CommitSummary {
     pub parents: Vec<String>,
 }
 
+/// Order in which history is listed
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub enum HistoryOrder {
+    /// Newest commit first
+    #[default]
+    Date,
+    /// Children before parents, keeping each line of development together
+    Topological,
+}
+
+/// Which commits a history listing walks
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HistoryRange {
+    /// Starting points: refs, SHAs, `A..B` or `A...B` ranges, or `^ref` to exclude a
+    /// ref's history. Empty means HEAD.
+    #[serde(default)]
+    pub revisions: Vec<String>,
+    #[serde(default)]
+    pub order: HistoryOrder,
+    /// Follow only the first parent of merge commits
+    #[serde(default)]
+    pub first_parent: bool,
+}
+
+/// Filters for searching commit history; unset filters match every commit
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CommitSearchQuery {
+    /// Regular expression matched against the full commit message
+    pub message: Option<String>,
+    /// Case-insensitive text matched against the author name or email
+    pub author: Option<String>,
+    /// Case-insensitive text matched against the committer name or email
+    pub committer: Option<String>,
+    /// Only commits committed at or after this time
+    pub since: Option<DateTime<Utc>>,
+    /// Only commits committed at or before this time
+    pub until: Option<DateTime<Utc>>,
+    /// Only commits touching one of these paths or directories
+    #[serde(default)]
+    pub paths: Vec<String>,
+    /// Only commits that change how often this text occurs in a file ("pickaxe")
+    pub content: Option<String>,
+}
+
+/// Commit that changed a file, with the file's path at that commit
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileHistoryEntry {
+    pub commit: CommitSummary,
+    pub path: String,
+    /// Path before this commit, when the commit renamed the file
+    pub old_path: Option<String>,
+    pub additions: usize,
+    pub deletions: usize,
+}
+
+/// Line segment of the commit graph, drawn from a row to the row below it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GraphEdge {
+    pub from_column: usize,
+    pub to_column: usize,
+    /// Leads to a second or later parent of a merge commit
+    pub is_merge: bool,
+}
+
+/// Commit with its position in the commit graph
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommitGraphRow {
+    pub commit: CommitSummary,
+    pub column: usize,
+    pub edges: Vec<GraphEdge>,
+}
+
+/// Lane assignment after a page of the graph, passed back to lay out the next page
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GraphLayoutState {
+    /// Number of commits already laid out
+    pub offset: usize,
+    /// Commit each lane is waiting for; `None` marks a free lane
+    pub lanes: Vec<Option<String>>,
+}
+
+/// Page of the commit graph
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommitGraphPage {
+    pub rows: Vec<CommitGraphRow>,
+    pub state: GraphLayoutState,
+}
+
 /// Author/Committer information
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Author {
@@ -100,6 +222,9 @@ pub struct FileDiff {
     pub binary: bool,
     pub additions: usize,
     pub deletions: usize,
+    /// Hunks were left out because the file exceeds the diff size limits
+    #[serde(default)]
+    pub truncated: bool,
 }
 
 /// Diff hunk
@@ -120,6 +245,55 @@ pub struct DiffLine {
     pub content: String,
     pub old_lineno: Option<usize>,
     pub new_lineno: Option<usize>,
+    /// Changed words within the line, filled in when word diff is requested
+    #[serde(default)]
+    pub word_ranges: Vec<WordRange>,
+}
+
+/// Changed span of a line, as a half-open range of characters (Unicode scalar
+/// values) from the start of its content. These are not UTF-16 code units, so a
+/// JavaScript consumer must index by code point, e.g. `Array.from(content)`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WordRange {
+    pub start: usize,
+    pub end: usize,
+}
+
+/// How whitespace differences are treated in a diff
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub enum WhitespaceMode {
+    #[default]
+    Show,
+    /// Ignore whitespace at the end of lines
+    IgnoreAtEol,
+    /// Ignore changes in the amount of whitespace (`git diff -b`)
+    IgnoreChanges,
+    /// Ignore all whitespace (`git diff -w`)
+    IgnoreAll,
+}
+
+/// Options shared by the diff commands.
+///
+/// Hunks of a diff that ignores whitespace don't match the file contents, so only
+/// hunks from a `Show` diff can be staged or discarded.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DiffRequestOptions {
+    /// Unchanged lines around each change; git's default of 3 when unset
+    pub context_lines: Option<u32>,
+    #[serde(default)]
+    pub whitespace: WhitespaceMode,
+    /// Similarity percentage from which a removed and an added file count as a
+    /// rename; no rename detection when unset
+    pub rename_threshold: Option<u16>,
+    /// Similarity percentage from which an added file counts as a copy of a
+    /// modified one; no copy detection when unset
+    pub copy_threshold: Option<u16>,
+    /// Fill in `word_ranges` for changed lines
+    #[serde(default)]
+    pub word_diff: bool,
+    /// Return every hunk of large files instead of truncating them
+    #[serde(default)]
+    pub full: bool,
 }
 
 /// Diff line type
@@ -153,6 +327,39 @@ pub struct Stash {
     pub branch: Option<String>,
 }
 
+/// Working tree of a repository; the main one is listed first
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Worktree {
+    /// Name of a linked worktree; `None` for the main working tree
+    pub name: Option<String>,
+    pub path: String,
+    /// Checked-out branch; `None` when detached or unreadable
+    pub branch: Option<String>,
+    pub head_sha: Option<String>,
+    pub is_main: bool,
+    /// Whether this is the working tree the list was read from
+    pub is_current: bool,
+    pub is_locked: bool,
+    pub lock_reason: Option<String>,
+    /// Its directory is gone, so `prune_worktrees` will remove it
+    pub is_prunable: bool,
+}
+
+/// Options for adding a linked worktree
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AddWorktreeOptions {
+    /// Relative paths are resolved from the directory holding the main working tree
+    pub path: String,
+    /// Defaults to the last component of `path`
+    pub name: Option<String>,
+    pub branch: String,
+    /// Create `branch` instead of checking out an existing one
+    #[serde(default)]
+    pub create_branch: bool,
+    /// Commit a new branch starts from; defaults to HEAD
+    pub start_point: Option<String>,
+}
+
 /// Remote information
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Remote {
@@ -178,6 +385,12 @@ pub struct MergeResult {
     pub success: bool,
     pub conflicts: Vec<String>,
     pub merged_commit: Option<String>,
+    /// Commits of a cherry-pick or revert left to apply after the one that
+    /// stopped with conflicts
+    pub unapplied: Vec<String>,
+    /// A squash merge stopped with conflicts; once they are resolved it ends in
+    /// a regular commit (or `continue_merge`), or is undone with `abort_merge`
+    pub squash_pending: bool,
 }
 
 /// Pull strategy
@@ -196,16 +409,203 @@ pub enum ResetMode {
     Hard,
 }
 
+/// Reset result, including any recovery point recorded before a hard reset
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResetResult {
+    pub head_sha: String,
+    pub backup_ref: Option<String>,
+    pub stash_sha: Option<String>,
+}
+
+/// Where discarded working tree changes are restored from
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub enum RestoreSource {
+    /// Drop unstaged changes, keeping what is staged
+    Index,
+    /// Drop staged and unstaged changes
+    Head,
+}
+
+/// Files affected by a discard or clean, with the backup taken beforehand
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiscardResult {
+    pub paths: Vec<String>,
+    pub backup_ref: Option<String>,
+    pub dry_run: bool,
+}
+
+/// Credential saved in the OS keyring for a remote host
+#[derive(Clone, Serialize, Deserialize)]
+pub enum HostCredential {
+    /// HTTPS username with a personal access token or password
+    Token { username: String, token: String },
+    /// SSH key pair on disk
+    SshKey {
+        username: String,
+        private_key_path: String,
+        public_key_path: Option<String>,
+        passphrase: Option<String>,
+    },
+}
+
+/// Secrets are redacted so credentials can appear in logs and traces
+impl fmt::Debug for HostCredential {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        const REDACTED: &str = "<redacted>";
+        match self {
+            Self::Token { username, .. } => f
+                .debug_struct("Token")
+                .field("username", username)
+                .field("token", &REDACTED)
+                .finish(),
+            Self::SshKey {
+                username,
+                private_key_path,
+                public_key_path,
+                passphrase,
+            } => f
+                .debug_struct("SshKey")
+                .field("username", username)
+                .field("private_key_path", private_key_path)
+                .field("public_key_path", public_key_path)
+                .field("passphrase", &passphrase.as_ref().map(|_| REDACTED))
+                .finish(),
+        }
+    }
+}
+
+/// Kind of credential saved for a host
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum CredentialKind {
+    Token,
+    SshKey,
+}
+
+/// Saved credential details that are safe to show, without the secret
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CredentialInfo {
+    pub host: String,
+    pub username: String,
+    pub kind: CredentialKind,
+}
+
+/// Stage of a network operation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum TransferStage {
+    /// Downloading objects
+    Receiving,
+    /// Resolving deltas of downloaded objects
+    Resolving,
+    /// Text sent by the remote, such as "Counting objects"
+    Remote,
+    /// Building the pack to push
+    Packing,
+    /// Uploading the pack
+    Uploading,
+    /// Remote reported the result for a pushed reference
+    UpdatingRef,
+    /// Writing files to the working tree after a clone
+    CheckingOut,
+}
+
+/// Progress event for a fetch, push, pull or clone
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransferProgress {
+    pub operation_id: String,
+    pub stage: TransferStage,
+    pub current: usize,
+    pub total: usize,
+    pub bytes: usize,
+    pub message: Option<String>,
+}
+
+/// What a repository watcher noticed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RepositoryChangeKind {
+    /// Working tree files or the index changed
+    Status,
+    /// HEAD moved to another branch or commit
+    Head,
+    /// Branches, tags or remote refs changed
+    Refs,
+}
+
+/// Debounced change notification for an open repository
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RepositoryChangeEvent {
+    /// Id of the repository that changed
+    pub repository: String,
+    pub kind: RepositoryChangeKind,
+    /// Changed paths relative to the working tree or the git directory; capped,
+    /// so a large change lists only some of them
+    pub paths: Vec<String>,
+}
+
+/// Options for cloning a repository
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CloneOptions {
+    pub url: String,
+    pub destination: String,
+    /// Branch to check out instead of the remote's default
+    pub branch: Option<String>,
+    /// Shallow clone with this many commits of history
+    pub depth: Option<u32>,
+    #[serde(default)]
+    pub recursive_submodules: bool,
+}
+
 /// Merge strategy
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum MergeStrategy {
+    /// Merge against a single merge base
     Resolve,
+    /// The default; merges multiple merge bases into a virtual base first
     Recursive,
+    /// Record the merge but keep the current branch's tree unchanged
     Ours,
+    /// Not a git strategy; rejected
     Theirs,
+    /// Only meaningful for more than one branch; rejected
     Octopus,
 }
 
+/// Merge mode
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum MergeMode {
+    /// Fast-forward when possible, otherwise create a merge commit
+    FastForward,
+    FastForwardOnly,
+    NoFastForward,
+    Squash,
+}
+
+/// Interactive rebase action
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum RebaseAction {
+    Pick,
+    Reword { message: String },
+    Squash,
+    Fixup,
+    Drop,
+}
+
+/// Interactive rebase plan step
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RebasePlanStep {
+    pub sha: String,
+    pub action: RebaseAction,
+}
+
+/// Rebase progress
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RebaseStatus {
+    pub in_progress: bool,
+    pub current_step: Option<usize>,
+    pub total_steps: usize,
+    pub conflicts: Vec<String>,
+    pub head_sha: Option<String>,
+}
+
 /// Conflict resolution
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ConflictResolution {
@@ -221,6 +621,16 @@ pub enum ConflictResolutionType {
     Manual { content: String },
 }
 
+/// Conflicted file contents from each side of a merge
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConflictVersions {
+    pub path: String,
+    pub ancestor: Option<String>,
+    pub ours: Option<String>,
+    pub theirs: Option<String>,
+    pub is_binary: bool,
+}
+
 /// Blame information
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BlameInfo {
@@ -236,4 +646,6 @@ pub struct BlameLine {
     pub commit_sha: String,
     pub author: String,
     pub timestamp: DateTime<Utc>,
+    pub original_path: Option<String>,
+    pub original_line_number: usize,
 }
@@ -1,13 +1,17 @@
 import { invoke } from '@tauri-apps/api/core';
 import type {
   RepositoryInfo,
+  OpenRepositoryInfo,
   RepositoryStatus,
   Branch,
   Commit,
   CommitSummary,
   FileDiff,
+  FileHistoryEntry,
   Remote,
   Stash,
+  Worktree,
+  AddWorktreeOptions,
 } from '../types/git';
 
 export class GitService {
@@ -28,43 +32,43 @@ export class GitService {
   /**
    * Get current repository status
    */
-  static async getStatus(): Promise<RepositoryStatus> {
-    return invoke<RepositoryStatus>('get_repository_status');
+  static async getStatus(includeIgnored = false, repoId?: string): Promise<RepositoryStatus> {
+    return invoke<RepositoryStatus>('get_repository_status', { includeIgnored, repoId });
   }
 
   /**
    * Get all branches (local and remote)
    */
-  static async getBranches(): Promise<Branch[]> {
-    return invoke<Branch[]>('get_branches');
+  static async getBranches(repoId?: string): Promise<Branch[]> {
+    return invoke<Branch[]>('get_branches', { repoId });
   }
 
   /**
    * Stage a file
    */
-  static async stageFile(filePath: string): Promise<void> {
-    return invoke('stage_file', { filePath });
+  static async stageFile(filePath: string, repoId?: string): Promise<void> {
+    return invoke('stage_file', { filePath, repoId });
   }
 
   /**
    * Stage all changes
    */
-  static async stageAll(): Promise<void> {
-    return invoke('stage_all');
+  static async stageAll(repoId?: string): Promise<void> {
+    return invoke('stage_all', { repoId });
   }
 
   /**
    * Unstage a file
    */
-  static async unstageFile(filePath: string): Promise<void> {
-    return invoke('unstage_file', { filePath });
+  static async unstageFile(filePath: string, repoId?: string): Promise<void> {
+    return invoke('unstage_file', { filePath, repoId });
   }
 
   /**
    * Unstage all changes
    */
-  static async unstageAll(): Promise<void> {
-    return invoke('unstage_all');
+  static async unstageAll(repoId?: string): Promise<void> {
+    return invoke('unstage_all', { repoId });
   }
 
   /**
@@ -73,34 +77,36 @@ export class GitService {
   static async createCommit(
     message: string,
     authorName: string,
-    authorEmail: string
+    authorEmail: string,
+    repoId?: string
   ): Promise<string> {
     return invoke<string>('create_commit', {
       message,
       authorName,
       authorEmail,
+      repoId,
     });
   }
 
   /**
    * Checkout a branch
    */
-  static async checkoutBranch(branchName: string): Promise<void> {
-    return invoke('checkout_branch', { branchName });
+  static async checkoutBranch(branchName: string, repoId?: string): Promise<void> {
+    return invoke('checkout_branch', { branchName, repoId });
   }
 
   /**
    * Create a new branch
    */
-  static async createBranch(name: string, from?: string): Promise<void> {
-    return invoke('create_branch', { name, from });
+  static async createBranch(name: string, from?: string, repoId?: string): Promise<void> {
+    return invoke('create_branch', { name, from, repoId });
   }
 
   /**
    * Delete a branch
    */
-  static async deleteBranch(name: string, force: boolean = false): Promise<void> {
-    return invoke('delete_branch', { name, force });
+  static async deleteBranch(name: string, force: boolean = false, repoId?: string): Promise<void> {
+    return invoke('delete_branch', { name, force, repoId });
   }
 
   /**
@@ -117,27 +123,53 @@ export class GitService {
     return invoke<string | null>('get_current_repository');
   }
 
+  /**
+   * List repositories that are currently open
+   */
+  static async listOpenRepositories(): Promise<OpenRepositoryInfo[]> {
+    return invoke<OpenRepositoryInfo[]>('list_open_repositories');
+  }
+
+  /**
+   * Make an open repository the one used when no repoId is given
+   */
+  static async setActiveRepository(repoId: string): Promise<void> {
+    return invoke<void>('set_active_repository', { repoId });
+  }
+
+  /**
+   * Close an open repository and stop watching it
+   */
+  static async closeRepository(repoId: string): Promise<void> {
+    return invoke<void>('close_repository', { repoId });
+  }
+
   /**
    * Get git configuration
    */
-  static async getConfig(): Promise<[string, string]> {
-    return invoke<[string, string]>('get_git_config');
+  static async getConfig(repoId?: string): Promise<[string, string]> {
+    return invoke<[string, string]>('get_git_config', { repoId });
   }
 
   /**
    * Get detailed git configuration (global and local)
    * Returns: [globalName, globalEmail, localName, localEmail]
    */
-  static async getConfigDetailed(): Promise<[string, string, string, string]> {
-    return invoke<[string, string, string, string]>('get_git_config_detailed');
+  static async getConfigDetailed(repoId?: string): Promise<[string, string, string, string]> {
+    return invoke<[string, string, string, string]>('get_git_config_detailed', { repoId });
   }
 
   /**
    * Set git configuration
    * @param global - true for global config, false for local (default: false)
    */
-  static async setConfig(name: string, email: string, global?: boolean): Promise<void> {
-    return invoke('set_git_config', { name, email, global });
+  static async setConfig(
+    name: string,
+    email: string,
+    global?: boolean,
+    repoId?: string
+  ): Promise<void> {
+    return invoke('set_git_config', { name, email, global, repoId });
   }
 
   // ===== Phase 2: History Operations =====
@@ -145,29 +177,37 @@ export class GitService {
   /**
    * Get paginated commit history
    */
-  static async getCommits(limit: number, offset: number): Promise<CommitSummary[]> {
-    return invoke<CommitSummary[]>('get_commits', { limit, offset });
+  static async getCommits(
+    limit: number,
+    offset: number,
+    repoId?: string
+  ): Promise<CommitSummary[]> {
+    return invoke<CommitSummary[]>('get_commits', { limit, offset, repoId });
   }
 
   /**
    * Get detailed commit information
    */
-  static async getCommitDetails(sha: string): Promise<Commit> {
-    return invoke<Commit>('get_commit_details', { sha });
+  static async getCommitDetails(sha: string, repoId?: string): Promise<Commit> {
+    return invoke<Commit>('get_commit_details', { sha, repoId });
   }
 
   /**
    * Get file changes for a specific commit
    */
-  static async getCommitDiff(sha: string): Promise<FileDiff[]> {
-    return invoke<FileDiff[]>('get_commit_diff', { sha });
+  static async getCommitDiff(sha: string, repoId?: string): Promise<FileDiff[]> {
+    return invoke<FileDiff[]>('get_commit_diff', { sha, repoId });
   }
 
   /**
-   * Get commit history for a specific file
+   * Get commit history for a specific file, following renames
    */
-  static async getFileHistory(filePath: string, limit: number): Promise<CommitSummary[]> {
-    return invoke<CommitSummary[]>('get_file_history', { filePath, limit });
+  static async getFileHistory(
+    filePath: string,
+    limit: number,
+    repoId?: string
+  ): Promise<FileHistoryEntry[]> {
+    return invoke<FileHistoryEntry[]>('get_file_history', { filePath, limit, repoId });
   }
 
   // ===== Phase 2: Diff Operations =====
@@ -175,22 +215,26 @@ export class GitService {
   /**
    * Get unstaged diff for a file
    */
-  static async getFileDiffUnstaged(filePath: string): Promise<FileDiff> {
-    return invoke<FileDiff>('get_file_diff_unstaged', { filePath });
+  static async getFileDiffUnstaged(filePath: string, repoId?: string): Promise<FileDiff> {
+    return invoke<FileDiff>('get_file_diff_unstaged', { filePath, repoId });
   }
 
   /**
    * Get staged diff for a file
    */
-  static async getFileDiffStaged(filePath: string): Promise<FileDiff> {
-    return invoke<FileDiff>('get_file_diff_staged', { filePath });
+  static async getFileDiffStaged(filePath: string, repoId?: string): Promise<FileDiff> {
+    return invoke<FileDiff>('get_file_diff_staged', { filePath, repoId });
   }
 
   /**
    * Get diff between two commits
    */
-  static async getDiffBetweenCommits(commit1: string, commit2: string): Promise<FileDiff[]> {
-    return invoke<FileDiff[]>('get_diff_between_commits', { commit1, commit2 });
+  static async getDiffBetweenCommits(
+    commit1: string,
+    commit2: string,
+    repoId?: string
+  ): Promise<FileDiff[]> {
+    return invoke<FileDiff[]>('get_diff_between_commits', { commit1, commit2, repoId });
   }
 
   // ===== Phase 2: Remote Operations =====
@@ -198,43 +242,48 @@ export class GitService {
   /**
    * List all remotes
    */
-  static async listRemotes(): Promise<Remote[]> {
-    return invoke<Remote[]>('list_remotes');
+  static async listRemotes(repoId?: string): Promise<Remote[]> {
+    return invoke<Remote[]>('list_remotes', { repoId });
   }
 
   /**
    * Add a new remote
    */
-  static async addRemote(name: string, url: string): Promise<void> {
-    return invoke('add_remote', { name, url });
+  static async addRemote(name: string, url: string, repoId?: string): Promise<void> {
+    return invoke('add_remote', { name, url, repoId });
   }
 
   /**
    * Remove a remote
    */
-  static async removeRemote(name: string): Promise<void> {
-    return invoke('remove_remote', { name });
+  static async removeRemote(name: string, repoId?: string): Promise<void> {
+    return invoke('remove_remote', { name, repoId });
   }
 
   /**
    * Fetch from remote
    */
-  static async fetchRemote(name: string): Promise<void> {
-    return invoke('fetch_remote', { name });
+  static async fetchRemote(name: string, repoId?: string): Promise<void> {
+    return invoke('fetch_remote', { name, repoId });
   }
 
   /**
    * Push to remote
    */
-  static async pushToRemote(remote: string, branch: string, force?: boolean): Promise<void> {
-    return invoke('push_to_remote', { remote, branch, force });
+  static async pushToRemote(
+    remote: string,
+    branch: string,
+    force?: boolean,
+    repoId?: string
+  ): Promise<void> {
+    return invoke('push_to_remote', { remote, branch, force, repoId });
   }
 
   /**
    * Pull from remote
    */
-  static async pullFromRemote(remote: string, branch: string): Promise<void> {
-    return invoke('pull_from_remote', { remote, branch });
+  static async pullFromRemote(remote: string, branch: string, repoId?: string): Promise<void> {
+    return invoke('pull_from_remote', { remote, branch, repoId });
   }
 
   // ===== Stash Operations =====
@@ -242,35 +291,79 @@ export class GitService {
   /**
    * List all stashes
    */
-  static async listStashes(): Promise<Stash[]> {
-    return invoke<Stash[]>('list_stashes');
+  static async listStashes(repoId?: string): Promise<Stash[]> {
+    return invoke<Stash[]>('list_stashes', { repoId });
   }
 
   /**
    * Create a new stash
    */
-  static async createStash(message?: string): Promise<string> {
-    return invoke<string>('create_stash', { message });
+  static async createStash(message?: string, repoId?: string): Promise<string> {
+    return invoke<string>('create_stash', { message, repoId });
   }
 
   /**
    * Apply a stash by index
    */
-  static async applyStash(index: number): Promise<void> {
-    return invoke('apply_stash', { index });
+  static async applyStash(index: number, repoId?: string): Promise<void> {
+    return invoke('apply_stash', { index, repoId });
   }
 
   /**
    * Pop a stash by index
    */
-  static async popStash(index: number): Promise<void> {
-    return invoke('pop_stash', { index });
+  static async popStash(index: number, repoId?: string): Promise<void> {
+    return invoke('pop_stash', { index, repoId });
   }
 
   /**
    * Drop a stash by index
    */
-  static async dropStash(index: number): Promise<void> {
-    return invoke('drop_stash', { index });
+  static async dropStash(index: number, repoId?: string): Promise<void> {
+    return invoke('drop_stash', { index, repoId });
+  }
+
+  // ===== Worktree Operations =====
+
+  /**
+   * List the main working tree and linked worktrees
+   */
+  static async listWorktrees(repoId?: string): Promise<Worktree[]> {
+    return invoke<Worktree[]>('list_worktrees', { repoId });
+  }
+
+  /**
+   * Add a worktree for a new or existing branch
+   */
+  static async addWorktree(options: AddWorktreeOptions, repoId?: string): Promise<Worktree> {
+    return invoke<Worktree>('add_worktree', { options, repoId });
+  }
+
+  /**
+   * Lock a worktree so it is not pruned
+   */
+  static async lockWorktree(name: string, reason?: string, repoId?: string): Promise<void> {
+    return invoke('lock_worktree', { name, reason, repoId });
+  }
+
+  /**
+   * Unlock a worktree
+   */
+  static async unlockWorktree(name: string, repoId?: string): Promise<void> {
+    return invoke('unlock_worktree', { name, repoId });
+  }
+
+  /**
+   * Prune worktrees whose directories were deleted
+   */
+  static async pruneWorktrees(repoId?: string): Promise<string[]> {
+    return invoke<string[]>('prune_worktrees', { repoId });
+  }
+
+  /**
+   * Open a worktree as the current repository
+   */
+  static async openWorktree(name: string, repoId?: string): Promise<RepositoryInfo> {
+    return invoke<RepositoryInfo>('open_worktree', { name, repoId });
   }
 }
@@ -1,5 +1,6 @@
 // Git repository types
 export interface RepositoryInfo {
+  id: string | null;
   path: string;
   name: string;
   current_branch: string | null;
@@ -9,14 +10,34 @@ export interface RepositoryInfo {
   remotes: string[];
 }
 
+export interface OpenRepositoryInfo {
+  id: string;
+  path: string;
+  name: string;
+  is_active: boolean;
+}
+
 export interface RepositoryStatus {
   staged: FileStatus[];
   unstaged: FileStatus[];
   untracked: FileStatus[];
   conflicted: FileStatus[];
+  ignored: FileStatus[];
+  submodules: SubmoduleStatus[];
   is_clean: boolean;
 }
 
+export interface SubmoduleStatus {
+  name: string;
+  path: string;
+  url: string | null;
+  recorded_sha: string | null;
+  checked_out_sha: string | null;
+  initialized: boolean;
+  out_of_date: boolean;
+  dirty: boolean;
+}
+
 export interface FileStatus {
   path: string;
   status: FileStatusType;
@@ -65,6 +86,14 @@ export interface CommitSummary {
   parents: string[];
 }
 
+export interface FileHistoryEntry {
+  commit: CommitSummary;
+  path: string;
+  old_path: string | null;
+  additions: number;
+  deletions: number;
+}
+
 export interface Author {
   name: string;
   email: string;
@@ -79,6 +108,7 @@ export interface FileDiff {
   binary: boolean;
   additions: number;
   deletions: number;
+  truncated: boolean;
 }
 
 export interface DiffHunk {
@@ -95,6 +125,13 @@ export interface DiffLine {
   content: string;
   old_lineno: number | null;
   new_lineno: number | null;
+  word_ranges?: WordRange[];
+}
+
+/** Changed span of a line in code points, not UTF-16 code units */
+export interface WordRange {
+  start: number;
+  end: number;
 }
 
 export type DiffLineType =
@@ -121,6 +158,26 @@ export interface Stash {
   branch: string | null;
 }
 
+export interface Worktree {
+  name: string | null;
+  path: string;
+  branch: string | null;
+  head_sha: string | null;
+  is_main: boolean;
+  is_current: boolean;
+  is_locked: boolean;
+  lock_reason: string | null;
+  is_prunable: boolean;
+}
+
+export interface AddWorktreeOptions {
+  path: string;
+  name?: string | null;
+  branch: string;
+  create_branch?: boolean;
+  start_point?: string | null;
+}
+
 export interface Remote {
   name: string;
   url: string;
@@ -140,6 +197,8 @@ export interface MergeResult {
   success: boolean;
   conflicts: string[];
   merged_commit: string | null;
+  unapplied: string[];
+  squash_pending: boolean;
 }
 
 export type PullStrategy = 'Merge' | 'Rebase' | 'FastForwardOnly';